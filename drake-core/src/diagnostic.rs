@@ -0,0 +1,373 @@
+//! Converting errors to diagnostics
+#[cfg(test)]
+mod tests;
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use codespan_reporting::diagnostic::{Diagnostic, Label, LabelStyle, Severity};
+use codespan_reporting::files::{self, Files};
+use core::fmt::Write;
+use core::ops::Range;
+use drake_render::json::string;
+use drake_types::error::Error;
+
+/// Gets a stable error code corresponding to the error.
+pub fn code<L>(err: &Error<L>) -> &'static str {
+    match err {
+        Error::ParseError { .. } => "E0001",
+        Error::DuplicateKey { .. } => "E0002",
+        Error::BuiltinNotFound { .. } => "E0003",
+        Error::NotSupported { .. } => "E0004",
+        Error::TypeMismatch { .. } => "E0005",
+        Error::NotRenderable { .. } => "E0006",
+        Error::UnknownFileType { .. } => "E0007",
+        Error::FileTypeNotSpecified => "E0008",
+        Error::KeyNotFound { .. } => "E0009",
+        Error::InaccessibleKey { .. } => "E0010",
+        Error::Overflow { .. } => "E0011",
+        Error::DivisionByZero { .. } => "E0012",
+        Error::BuiltinReadOnly { .. } => "E0013",
+        Error::BuiltinWriteOnly { .. } => "E0014",
+        Error::FieldNotFound { .. } => "E0015",
+        Error::IndexOutOfRange { .. } => "E0016",
+        Error::PatternMismatch { .. } => "E0017",
+        Error::LengthMismatch { .. } => "E0018",
+        Error::MissingKey { .. } => "E0019",
+        Error::ArityMismatch { .. } => "E0020",
+        Error::MissingArgument { .. } => "E0021",
+        Error::UnknownArgument { .. } => "E0022",
+        Error::DuplicateArgument { .. } => "E0023",
+        Error::RecursionLimit { .. } => "E0024",
+        Error::FunctionNotRenderable { .. } => "E0025",
+        Error::ImportCycle { .. } => "E0026",
+        Error::ModuleNotFound { .. } => "E0027",
+        Error::InvalidData { .. } => "E0028",
+        _ => "E9999",
+    }
+}
+
+/// Converts an error occurred in the file to a diagnostic.
+pub fn diagnostic<FileId: Copy>(err: &Error<usize>, file_id: FileId) -> Diagnostic<FileId> {
+    let diag = match err {
+        Error::Unexpected => Diagnostic::bug(),
+        _ => Diagnostic::error(),
+    }
+    .with_code(code(err));
+
+    match err {
+        Error::ParseError { expects, span } => {
+            diag.with_message("unexpected token")
+                .with_labels(vec![Label::primary(file_id, span.clone())
+                    .with_message(format!("expected {expects}"))])
+        }
+        Error::DuplicateKey { found, existing } => {
+            let mut labels =
+                vec![Label::primary(file_id, found.clone()).with_message("defined again here")];
+            if let Some(existing) = existing {
+                labels.push(
+                    Label::secondary(file_id, existing.clone()).with_message("first defined here"),
+                );
+            }
+            diag.with_message("the key is already defined")
+                .with_labels(labels)
+        }
+        Error::BuiltinNotFound { span } => diag
+            .with_message("no such built-in key")
+            .with_labels(vec![Label::primary(file_id, span.clone())])
+            .with_notes(vec![String::from(
+                "available built-in keys are `@output`, `@filetype`, `@true`, `@false`, `@null`, \
+                 `@inf`, `@nan`, `@map`, and `@filter`",
+            )]),
+        Error::BuiltinReadOnly { span } => {
+            diag.with_message(err.to_string())
+                .with_labels(vec![
+                    Label::primary(file_id, span.clone()).with_message("can't be assigned")
+                ])
+        }
+        Error::BuiltinWriteOnly { span } => {
+            diag.with_message(err.to_string())
+                .with_labels(vec![
+                    Label::primary(file_id, span.clone()).with_message("can't be referred")
+                ])
+        }
+        Error::NotSupported { span, .. } => diag
+            .with_message(err.to_string())
+            .with_labels(vec![Label::primary(file_id, span.clone())]),
+        Error::TypeMismatch { expected, span } => {
+            diag.with_message("mismatched types")
+                .with_labels(vec![Label::primary(file_id, span.clone())
+                    .with_message(format!("expected {expected}"))])
+        }
+        Error::NotRenderable { filetype, span } => diag
+            .with_message(err.to_string())
+            .with_labels(vec![Label::primary(file_id, span.clone()).with_message(
+                format!("this value can't be expressed in {filetype}"),
+            )]),
+        Error::UnknownFileType { span } => diag
+            .with_message(err.to_string())
+            .with_labels(vec![Label::primary(file_id, span.clone())])
+            .with_notes(vec![String::from(
+                "supported file types are `json`, `yaml`, and `toml`",
+            )]),
+        Error::FileTypeNotSpecified => {
+            diag.with_message(err.to_string())
+                .with_notes(vec![String::from(
+                    "specify `@filetype`, or `@output` with a supported extension",
+                )])
+        }
+        Error::KeyNotFound { span } => diag
+            .with_message(err.to_string())
+            .with_labels(vec![
+                Label::primary(file_id, span.clone()).with_message("not found in this scope")
+            ])
+            .with_notes(vec![String::from(
+                "keys in the root scope can be referred with a leading `.`, like `.key`",
+            )]),
+        Error::InaccessibleKey { found, defined } => {
+            diag.with_message(err.to_string()).with_labels(vec![
+                Label::primary(file_id, found.clone()).with_message("referred here"),
+                Label::secondary(file_id, defined.clone())
+                    .with_message("defined as a local key of another table"),
+            ])
+        }
+        Error::Overflow { span } => diag
+            .with_message(err.to_string())
+            .with_labels(vec![
+                Label::primary(file_id, span.clone()).with_message("this operation overflows")
+            ])
+            .with_notes(vec![String::from(
+                "integers must be from -9223372036854775808 to 18446744073709551615",
+            )]),
+        Error::DivisionByZero { span } => {
+            diag.with_message(err.to_string())
+                .with_labels(vec![
+                    Label::primary(file_id, span.clone()).with_message("the divisor is zero")
+                ])
+        }
+        Error::FieldNotFound { span } => {
+            diag.with_message(err.to_string())
+                .with_labels(vec![
+                    Label::primary(file_id, span.clone()).with_message("not found in the table")
+                ])
+        }
+        Error::IndexOutOfRange { len, span } => {
+            diag.with_message(err.to_string())
+                .with_labels(vec![Label::primary(file_id, span.clone())
+                    .with_message(format!("the length is {len}"))])
+        }
+        Error::PatternMismatch {
+            expected,
+            pattern,
+            span,
+        } => diag.with_message(err.to_string()).with_labels(vec![
+            Label::primary(file_id, span.clone()).with_message(format!("expected {expected}")),
+            Label::secondary(file_id, pattern.clone()).with_message("destructed by this pattern"),
+        ]),
+        Error::LengthMismatch {
+            expected,
+            rest,
+            found,
+            pattern,
+            span,
+        } => diag.with_message(err.to_string()).with_labels(vec![
+            Label::primary(file_id, span.clone())
+                .with_message(format!("this array has {}", elements(*found))),
+            Label::secondary(file_id, pattern.clone()).with_message(if *rest {
+                format!("expected at least {}", elements(*expected))
+            } else {
+                format!("expected {}", elements(*expected))
+            }),
+        ]),
+        Error::MissingKey { found, span } => diag
+            .with_message(err.to_string())
+            .with_labels(vec![
+                Label::primary(file_id, found.clone()).with_message("required here"),
+                Label::secondary(file_id, span.clone()).with_message("destructed table"),
+            ])
+            .with_notes(vec![String::from(
+                "a default value can be specified like `{ key ? default }`",
+            )]),
+        Error::ArityMismatch {
+            expected,
+            found,
+            span,
+            defined,
+        } => diag.with_message(err.to_string()).with_labels(vec![
+            Label::primary(file_id, span.clone())
+                .with_message(format!("{} given", arguments(*found))),
+            Label::secondary(file_id, defined.clone())
+                .with_message(format!("takes {}", arguments(*expected))),
+        ]),
+        Error::MissingArgument { parameter, span } => {
+            diag.with_message(err.to_string()).with_labels(vec![
+                Label::primary(file_id, span.clone()).with_message("called without the argument"),
+                Label::secondary(file_id, parameter.clone()).with_message("parameter defined here"),
+            ])
+        }
+        Error::UnknownArgument { span, defined } => {
+            diag.with_message(err.to_string()).with_labels(vec![
+                Label::primary(file_id, span.clone()).with_message("unknown parameter"),
+                Label::secondary(file_id, defined.clone()).with_message("function defined here"),
+            ])
+        }
+        Error::DuplicateArgument { found, existing } => {
+            diag.with_message(err.to_string()).with_labels(vec![
+                Label::primary(file_id, found.clone()).with_message("specified again here"),
+                Label::secondary(file_id, existing.clone()).with_message("first specified here"),
+            ])
+        }
+        Error::RecursionLimit { limit, span } => diag
+            .with_message(err.to_string())
+            .with_labels(vec![
+                Label::primary(file_id, span.clone()).with_message("called here")
+            ])
+            .with_notes(vec![format!(
+                "function calls can be nested up to {limit} times"
+            )]),
+        Error::FunctionNotRenderable { span } => diag
+            .with_message(err.to_string())
+            .with_labels(vec![
+                Label::primary(file_id, span.clone()).with_message("this value is a function")
+            ])
+            .with_notes(vec![String::from(
+                "functions can be bound to local keys like `fn _name(...) = ...`",
+            )]),
+        Error::ImportCycle { chain, span } => diag
+            .with_message(err.to_string())
+            .with_labels(vec![
+                Label::primary(file_id, span.clone()).with_message("imported here")
+            ])
+            .with_notes(vec![format!("the import chain is {}", chain.join(" → "))]),
+        Error::ModuleNotFound { span } => {
+            diag.with_message(err.to_string())
+                .with_labels(vec![
+                    Label::primary(file_id, span.clone()).with_message("no such file")
+                ])
+        }
+        Error::InvalidData { message, span, .. } => {
+            diag.with_message(err.to_string())
+                .with_labels(vec![
+                    Label::primary(file_id, span.clone()).with_message(message.clone())
+                ])
+        }
+        _ => diag.with_message(err.to_string()),
+    }
+}
+
+fn elements(len: usize) -> String {
+    match len {
+        1 => String::from("1 element"),
+        _ => format!("{len} elements"),
+    }
+}
+
+fn arguments(len: usize) -> String {
+    match len {
+        1 => String::from("1 argument"),
+        _ => format!("{len} arguments"),
+    }
+}
+
+/// Serializes a diagnostic occurred in the file to a JSON object.
+///
+/// The primary span and its location are `null` if the diagnostic has no labels.
+pub fn to_json<'a, F>(
+    diag: &Diagnostic<F::FileId>,
+    file_id: F::FileId,
+    files: &'a F,
+) -> Result<String, files::Error>
+where
+    F: Files<'a>,
+    F::FileId: Copy,
+{
+    let mut buf = String::from("{\"code\":");
+    match diag.code {
+        Some(ref code) => string(&mut buf, code),
+        None => buf.push_str("null"),
+    }
+    buf.push_str(",\"severity\":");
+    string(&mut buf, severity(diag.severity));
+    buf.push_str(",\"message\":");
+    string(&mut buf, &diag.message);
+    buf.push_str(",\"file\":");
+    string(&mut buf, &files.name(file_id)?.to_string());
+
+    let primary = diag
+        .labels
+        .iter()
+        .find(|label| label.style == LabelStyle::Primary);
+    match primary {
+        Some(label) => span(&mut buf, label.file_id, label.range.clone(), files)?,
+        None => buf.push_str(",\"span\":null,\"start\":null,\"end\":null"),
+    }
+
+    buf.push_str(",\"labels\":[");
+    for (i, label) in diag.labels.iter().enumerate() {
+        if i != 0 {
+            buf.push(',');
+        }
+        buf.push_str("{\"style\":");
+        string(
+            &mut buf,
+            match label.style {
+                LabelStyle::Primary => "primary",
+                LabelStyle::Secondary => "secondary",
+            },
+        );
+        buf.push_str(",\"file\":");
+        string(&mut buf, &files.name(label.file_id)?.to_string());
+        span(&mut buf, label.file_id, label.range.clone(), files)?;
+        buf.push_str(",\"message\":");
+        string(&mut buf, &label.message);
+        buf.push('}');
+    }
+
+    buf.push_str("],\"notes\":[");
+    for (i, note) in diag.notes.iter().enumerate() {
+        if i != 0 {
+            buf.push(',');
+        }
+        string(&mut buf, note);
+    }
+    buf.push_str("]}");
+    Ok(buf)
+}
+
+/// Writes a byte span and one-based line/column numbers of both ends.
+fn span<'a, F: Files<'a>>(
+    buf: &mut String,
+    file_id: F::FileId,
+    range: Range<usize>,
+    files: &'a F,
+) -> Result<(), files::Error>
+where
+    F::FileId: Copy,
+{
+    let start = files.location(file_id, range.start)?;
+    let end = files.location(file_id, range.end)?;
+    write!(
+        buf,
+        ",\"span\":{{\"start\":{},\"end\":{}}}\
+         ,\"start\":{{\"line\":{},\"column\":{}}}\
+         ,\"end\":{{\"line\":{},\"column\":{}}}",
+        range.start,
+        range.end,
+        start.line_number,
+        start.column_number,
+        end.line_number,
+        end.column_number,
+    )
+    .unwrap();
+    Ok(())
+}
+
+fn severity(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Bug => "bug",
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note => "note",
+        Severity::Help => "help",
+    }
+}
@@ -0,0 +1,40 @@
+use alloc::string::String;
+use drake_types::error::Error;
+
+use crate::module::Module;
+
+#[test]
+fn to_json() {
+    let module = Module::new(
+        String::from("dir/\"a\".drake"),
+        String::from("a = 1\nb = \"x\"\n"),
+    );
+    let diag = super::diagnostic(
+        &Error::TypeMismatch {
+            expected: "table",
+            span: 10..13,
+        },
+        (),
+    );
+    assert_eq!(
+        super::to_json(&diag, (), &module).unwrap(),
+        String::from(
+            "{\"code\":\"E0005\",\"severity\":\"error\",\"message\":\"mismatched types\",\
+             \"file\":\"dir/\\\"a\\\".drake\",\"span\":{\"start\":10,\"end\":13},\
+             \"start\":{\"line\":2,\"column\":5},\"end\":{\"line\":2,\"column\":8},\
+             \"labels\":[{\"style\":\"primary\",\"file\":\"dir/\\\"a\\\".drake\",\
+             \"span\":{\"start\":10,\"end\":13},\"start\":{\"line\":2,\"column\":5},\
+             \"end\":{\"line\":2,\"column\":8},\"message\":\"expected table\"}],\"notes\":[]}"
+        )
+    );
+
+    let diag = super::diagnostic(&Error::<usize>::Unexpected, ());
+    assert_eq!(
+        super::to_json(&diag, (), &module).unwrap(),
+        String::from(
+            "{\"code\":\"E9999\",\"severity\":\"bug\",\"message\":\"an unexpected error occurred\",\
+             \"file\":\"dir/\\\"a\\\".drake\",\"span\":null,\"start\":null,\"end\":null,\
+             \"labels\":[],\"notes\":[]}"
+        )
+    );
+}
@@ -1,3 +1,6 @@
+#[cfg(test)]
+mod tests;
+
 use core::ops::Range;
 
 use alloc::string::String;
@@ -51,9 +54,9 @@ impl Source {
         use core::cmp::Ordering;
 
         match self.line_starts.len().cmp(&(line + 1)) {
-            Ordering::Less => Ok(self.line_starts[line]..self.line_starts[line + 1]),
+            Ordering::Greater => Ok(self.line_starts[line]..self.line_starts[line + 1]),
             Ordering::Equal => Ok(self.line_starts[line]..self.source.len()),
-            Ordering::Greater => Err(self.line_starts.len() - 1),
+            Ordering::Less => Err(self.line_starts.len() - 1),
         }
     }
 }
@@ -0,0 +1,25 @@
+use alloc::string::String;
+
+use super::Source;
+
+#[test]
+fn line_index() {
+    let source = Source::new(String::from("a = 1\nb = 2\n"));
+    assert_eq!(source.line_index(0), 0);
+    assert_eq!(source.line_index(5), 0);
+    assert_eq!(source.line_index(6), 1);
+    assert_eq!(source.line_index(12), 2);
+}
+
+#[test]
+fn line_range() {
+    let source = Source::new(String::from("a = 1\nb = 2"));
+    assert_eq!(source.line_range(0), Ok(0..6));
+    assert_eq!(source.line_range(1), Ok(6..11));
+    assert_eq!(source.line_range(2), Err(1));
+    assert_eq!(source.line_range(100), Err(1));
+
+    let source = Source::new(String::new());
+    assert_eq!(source.line_range(0), Ok(0..0));
+    assert_eq!(source.line_range(1), Err(0));
+}
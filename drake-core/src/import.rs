@@ -0,0 +1,96 @@
+//! Resolving imports of other files
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+use drake_ir::Importer;
+use drake_types::ast::{
+    Expression, ExpressionKind, Pattern, PatternKind, Statement, StatementKind,
+};
+use drake_types::error::Error;
+use drake_types::ir::Expr;
+
+/// Imported values indexed by the start positions of the import expressions.
+pub struct Imports(pub BTreeMap<usize, Result<Expr<usize>, Error<usize>>>);
+
+impl Importer<usize> for Imports {
+    fn import(&self, _: &str, span: Range<usize>) -> Result<Expr<usize>, Error<usize>> {
+        match self.0.get(&span.start) {
+            Some(res) => res.clone(),
+            None => Err(Error::Unexpected),
+        }
+    }
+}
+
+/// Collects paths and spans of import expressions in the AST.
+pub fn imports(ast: &[Statement<usize>]) -> Vec<(String, Range<usize>)> {
+    let mut res = Vec::new();
+    for stmt in ast {
+        match stmt.kind {
+            StatementKind::ValueBinding(ref pat, ref expr) => {
+                pattern(pat, &mut res);
+                expression(expr, &mut res);
+            }
+            StatementKind::TableHeader(_, ref pat, ref default) => {
+                pattern(pat, &mut res);
+                if let Some(default) = default {
+                    expression(default, &mut res);
+                }
+            }
+            StatementKind::ExpressionFunction(_, _, ref body) => expression(body, &mut res),
+            _ => {}
+        }
+    }
+    res
+}
+
+fn pattern(pat: &Pattern<usize>, res: &mut Vec<(String, Range<usize>)>) {
+    match pat.kind {
+        PatternKind::Array(ref pats, ref rest) => {
+            for pat in pats.iter().chain(rest.as_deref()) {
+                pattern(pat, res);
+            }
+        }
+        PatternKind::Table(ref pats) => {
+            for key_pat in pats {
+                if let Some(ref pat) = key_pat.pattern {
+                    pattern(pat, res);
+                }
+                if let Some(ref default) = key_pat.default {
+                    expression(default, res);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn expression(expr: &Expression<usize>, res: &mut Vec<(String, Range<usize>)>) {
+    match expr.kind {
+        ExpressionKind::Import(ref path) => res.push((path.clone(), expr.span.clone())),
+        ExpressionKind::Array(ref elems) => {
+            for elem in elems {
+                expression(elem, res);
+            }
+        }
+        ExpressionKind::InlineTable(ref pairs) => {
+            for (_, expr) in pairs {
+                expression(expr, res);
+            }
+        }
+        ExpressionKind::BinaryOp(_, ref lhs, ref rhs) | ExpressionKind::Index(ref lhs, ref rhs) => {
+            expression(lhs, res);
+            expression(rhs, res);
+        }
+        ExpressionKind::UnaryOp(_, ref expr)
+        | ExpressionKind::Field(ref expr, _)
+        | ExpressionKind::InlineFunction(_, ref expr) => expression(expr, res),
+        ExpressionKind::Call(ref func, ref args, ref named) => {
+            expression(func, res);
+            for arg in args.iter().chain(named.iter().map(|(_, arg)| arg)) {
+                expression(arg, res);
+            }
+        }
+        _ => {}
+    }
+}
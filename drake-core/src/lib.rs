@@ -1,22 +1,37 @@
 #![no_std]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
+pub mod diagnostic;
 mod files;
+mod import;
+pub mod loader;
 pub mod module;
+#[cfg(test)]
+mod tests;
 
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
 use alloc::string::String;
 use alloc::vec::Vec;
+use codespan_reporting::diagnostic::{Diagnostic, LabelStyle};
 use codespan_reporting::files::{Error, Files};
+use codespan_reporting::term::termcolor::WriteColor;
 use core::ops::Range;
+use futures_util::future::{FutureExt, LocalBoxFuture};
 
+use import::{imports, Imports};
+use loader::Loader;
 #[doc(inline)]
 pub use module::Module;
+use module::{FileType, Output};
 
 /// A struct contains all runtime informations
 #[derive(Debug, Clone)]
-#[allow(dead_code)]
 pub struct Runtime {
     modules: Vec<Module>,
+    loader: Rc<dyn Loader>,
 }
 
 impl<'a> Files<'a> for Runtime {
@@ -51,13 +66,37 @@ impl<'a> Files<'a> for Runtime {
 
 impl Runtime {
     /// Creates a new instance.
+    ///
+    /// Imported files are loaded from the file system with the `std` feature, or can't be loaded
+    /// without it. Use [`with_loader`](Self::with_loader) to load files from other sources.
     #[inline]
     pub fn new(name: String, source: String) -> Self {
+        #[cfg(feature = "std")]
+        let loader = loader::FsLoader;
+        #[cfg(not(feature = "std"))]
+        let loader = loader::MemoryLoader::new();
+        Self::with_loader(name, source, loader)
+    }
+
+    /// Creates a new instance loading imported files by the loader.
+    ///
+    /// The name of the module is resolved by [`Loader::resolve`] like imported paths, so that
+    /// imports of the module itself are found.
+    #[inline]
+    pub fn with_loader<T: Loader + 'static>(name: String, source: String, loader: T) -> Self {
+        let name = loader.resolve("", &name);
         Self {
             modules: alloc::vec![Module::new(name, source)],
+            loader: Rc::new(loader),
         }
     }
 
+    /// Gets a reference of the loader.
+    #[inline]
+    pub fn get_loader(&self) -> &dyn Loader {
+        self.loader.as_ref()
+    }
+
     /// Gets a slice of modules indexed by identifiers.
     #[inline]
     pub fn get_modules(&self) -> &[Module] {
@@ -70,6 +109,127 @@ impl Runtime {
         self.modules.get(id)
     }
 
+    /// Gets a mutable reference of a module corresponding to given identifier.
+    #[inline]
+    pub fn get_module_mut(&mut self, id: usize) -> Option<&mut Module> {
+        self.modules.get_mut(id)
+    }
+
+    /// Adds a module, and returns its identifier.
+    pub fn add_module(&mut self, name: String, source: String) -> usize {
+        let base = self.next_base();
+        self.modules.push(Module::with_base(name, source, base));
+        self.modules.len() - 1
+    }
+
+    /// Adds a data file in `filetype` as a module, and returns its identifier.
+    pub fn add_data(&mut self, name: String, source: String, filetype: FileType) -> usize {
+        let base = self.next_base();
+        self.modules
+            .push(Module::with_data(name, source, base, filetype));
+        self.modules.len() - 1
+    }
+
+    fn next_base(&self) -> usize {
+        // Leaving a gap so that the end of a module is not the start of the next one.
+        self.modules
+            .last()
+            .map_or(0, |m| m.get_base() + m.get_source().len() + 1)
+    }
+
+    /// Interprets the module and other files imported by it.
+    ///
+    /// Imported files are added to the runtime as modules named by resolved paths. Files with
+    /// extensions of JSON, YAML, or TOML are added as data files.
+    pub async fn evaluate(&mut self, id: usize) {
+        self.evaluate_module(id, &mut Vec::new()).await;
+    }
+
+    /// Renders the module after interpreting it and other files imported by it.
+    ///
+    /// See [`Module::render`] for details. Nothing is rendered if any errors have occurred in the
+    /// module or imported files.
+    pub async fn render(&mut self, id: usize, filetype: Option<FileType>) -> Option<Output> {
+        self.evaluate(id).await;
+        if self.modules.iter().any(|m| !m.get_errors().is_empty()) {
+            return None;
+        }
+        self.modules[id].render(filetype).await
+    }
+
+    fn evaluate_module<'a>(
+        &'a mut self,
+        id: usize,
+        chain: &'a mut Vec<usize>,
+    ) -> LocalBoxFuture<'a, ()> {
+        async move {
+            let module = &self.modules[id];
+            if module.get_ir().is_some() || module.get_data_type().is_some() {
+                return;
+            }
+
+            chain.push(id);
+            let sites = imports(self.modules[id].parse().await);
+            let mut tables = BTreeMap::new();
+            for (path, span) in sites {
+                let name = self.loader.resolve(self.modules[id].get_name(), &path);
+                let res = match self.get_module_by_name(&name) {
+                    Some((imported, _)) if chain.contains(&imported) => {
+                        let start = chain.iter().position(|i| *i == imported).unwrap();
+                        Err(drake_types::error::Error::ImportCycle {
+                            chain: chain[start..]
+                                .iter()
+                                .map(|i| String::from(self.modules[*i].get_name()))
+                                .chain(core::iter::once(name))
+                                .collect(),
+                            span: span.clone(),
+                        })
+                    }
+                    Some((imported, _)) => Ok(imported),
+                    None => match self.loader.load(&name) {
+                        Some(source) => Ok(match FileType::from_path(&name) {
+                            Some(filetype) => self.add_data(name, source, filetype),
+                            None => self.add_module(name, source),
+                        }),
+                        None => {
+                            Err(drake_types::error::Error::ModuleNotFound { span: span.clone() })
+                        }
+                    },
+                };
+
+                let res = match res {
+                    Ok(imported) => {
+                        self.evaluate_module(imported, chain).await;
+                        Ok(self.modules[imported].export().await.clone())
+                    }
+                    Err(err) => Err(err),
+                };
+                tables.insert(span.start, res);
+            }
+
+            self.modules[id].evaluate_with(&Imports(tables)).await;
+            chain.pop();
+        }
+        .boxed_local()
+    }
+
+    /// Gets the identifier of the module containing the position.
+    fn locate(&self, pos: usize) -> Option<usize> {
+        self.modules.iter().rposition(|m| m.get_base() <= pos)
+    }
+
+    /// Moves labels of the diagnostic to the modules containing them.
+    fn relocate(&self, mut diag: Diagnostic<usize>) -> Diagnostic<usize> {
+        for label in diag.labels.iter_mut() {
+            if let Some(id) = self.locate(label.range.start) {
+                let base = self.modules[id].get_base();
+                label.file_id = id;
+                label.range = label.range.start - base..label.range.end - base;
+            }
+        }
+        diag
+    }
+
     /// Gets a reference of a module corresponding to given name.
     #[inline]
     pub fn get_module_by_name<S: AsRef<str>>(&self, name: S) -> Option<(usize, &Module)> {
@@ -78,4 +238,57 @@ impl Runtime {
             .enumerate()
             .find(|(_, m)| m.get_name() == name.as_ref())
     }
+
+    /// Gets diagnostics for errors occurred in all modules.
+    pub fn diagnostics(&self) -> Vec<Diagnostic<usize>> {
+        self.modules
+            .iter()
+            .enumerate()
+            .flat_map(|(id, module)| {
+                module
+                    .get_errors()
+                    .iter()
+                    .map(move |err| self.relocate(diagnostic::diagnostic(err, id)))
+            })
+            .collect()
+    }
+
+    /// Serializes diagnostics for errors occurred in all modules to JSON objects, one per line.
+    pub fn diagnostics_json(&self) -> Result<String, Error> {
+        let mut buf = String::new();
+        for (id, module) in self.modules.iter().enumerate() {
+            for err in module.get_errors() {
+                let diag = self.relocate(diagnostic::diagnostic(err, id));
+                // Errors in imported files are reported in the files.
+                let file_id = diag
+                    .labels
+                    .iter()
+                    .find(|label| label.style == LabelStyle::Primary)
+                    .map_or(id, |label| label.file_id);
+                buf.push_str(&diagnostic::to_json(&diag, file_id, self)?);
+                buf.push('\n');
+            }
+        }
+        Ok(buf)
+    }
+
+    /// Writes diagnostics for errors occurred in all modules.
+    pub fn emit_diagnostics(&self, writer: &mut dyn WriteColor) -> Result<(), Error> {
+        let config = codespan_reporting::term::Config::default();
+        for diag in self.diagnostics() {
+            codespan_reporting::term::emit(writer, &config, self, &diag)?;
+        }
+        Ok(())
+    }
+
+    /// Prints diagnostics for errors occurred in all modules to the standard error.
+    #[cfg(feature = "std")]
+    pub fn print_diagnostics(
+        &self,
+        color: codespan_reporting::term::termcolor::ColorChoice,
+    ) -> Result<(), Error> {
+        let stderr = codespan_reporting::term::termcolor::StandardStream::stderr(color);
+        let mut lock = stderr.lock();
+        self.emit_diagnostics(&mut lock)
+    }
 }
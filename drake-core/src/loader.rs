@@ -0,0 +1,105 @@
+//! Loading files imported by modules
+#[cfg(test)]
+mod tests;
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+
+/// A source of files imported by modules
+pub trait Loader: Debug {
+    /// Resolves the path imported by the module named `importer` to the name of the file.
+    ///
+    /// By default, paths are separated by slashes (`/`) and resolved relative to the directory of
+    /// the importer, normalizing `.` and `..`. Backslashes (`\`) and prefixes like `C:` are not
+    /// recognized, so loaders for Windows paths should override this method.
+    fn resolve(&self, importer: &str, path: &str) -> String {
+        let dir = match importer.rsplit_once('/') {
+            Some((dir, _)) if !path.starts_with('/') => dir,
+            _ => "",
+        };
+        let absolute = path.starts_with('/') || importer.starts_with('/');
+
+        let mut segments = Vec::new();
+        for segment in dir.split('/').chain(path.split('/')) {
+            match segment {
+                "" | "." => {}
+                ".." if matches!(segments.last(), Some(&last) if last != "..") => {
+                    segments.pop();
+                }
+                // The parent of the root directory is itself.
+                ".." if absolute => {}
+                _ => segments.push(segment),
+            }
+        }
+
+        let path = segments.join("/");
+        if absolute {
+            alloc::format!("/{path}")
+        } else {
+            path
+        }
+    }
+
+    /// Reads the contents of the file resolved by [`resolve`](Self::resolve), or returns `None`
+    /// if it can't be read.
+    fn load(&self, name: &str) -> Option<String>;
+}
+
+/// A loader reading files from the file system
+///
+/// Paths are resolved by the default [`Loader::resolve`], so only slashes are separators.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FsLoader;
+
+#[cfg(feature = "std")]
+impl Loader for FsLoader {
+    #[inline]
+    fn load(&self, name: &str) -> Option<String> {
+        std::fs::read_to_string(name).ok()
+    }
+}
+
+/// A loader serving files from memory, useful for tests or embedding
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryLoader {
+    files: BTreeMap<String, String>,
+}
+
+impl MemoryLoader {
+    /// Creates a new instance without files.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a file, and returns the old contents if the name is already used.
+    #[inline]
+    pub fn insert(&mut self, name: String, source: String) -> Option<String> {
+        self.files.insert(name, source)
+    }
+
+    /// Removes a file, and returns its contents.
+    #[inline]
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        self.files.remove(name)
+    }
+}
+
+impl FromIterator<(String, String)> for MemoryLoader {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = (String, String)>>(iter: T) -> Self {
+        Self {
+            files: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Loader for MemoryLoader {
+    #[inline]
+    fn load(&self, name: &str) -> Option<String> {
+        self.files.get(name).cloned()
+    }
+}
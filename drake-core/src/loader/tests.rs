@@ -0,0 +1,67 @@
+use alloc::string::String;
+
+use super::{Loader, MemoryLoader};
+
+#[test]
+fn resolve() {
+    let loader = MemoryLoader::new();
+    let cases = [
+        ("main.drake", "a.drake", "a.drake"),
+        ("main.drake", "./dir/./a.drake", "dir/a.drake"),
+        ("dir/main.drake", "a.drake", "dir/a.drake"),
+        ("dir/main.drake", "../a.drake", "a.drake"),
+        ("dir/main.drake", "sub/../../a.drake", "a.drake"),
+        ("main.drake", "../a.drake", "../a.drake"),
+        ("../main.drake", "../a.drake", "../../a.drake"),
+        ("", "./main.drake", "main.drake"),
+        // Absolute paths
+        ("dir/main.drake", "/a.drake", "/a.drake"),
+        ("/dir/main.drake", "a.drake", "/dir/a.drake"),
+        ("/dir/main.drake", "../../a.drake", "/a.drake"),
+        // Backslashes are not separators.
+        ("dir\\main.drake", "a.drake", "a.drake"),
+        ("main.drake", "dir\\a.drake", "dir\\a.drake"),
+    ];
+    for (importer, path, name) in cases {
+        assert_eq!(loader.resolve(importer, path), name, "{importer} {path}");
+    }
+}
+
+#[test]
+fn memory() {
+    let mut loader: MemoryLoader = [(String::from("a.drake"), String::from("a = 1\n"))]
+        .into_iter()
+        .collect();
+    assert_eq!(loader.load("a.drake"), Some(String::from("a = 1\n")));
+    assert_eq!(loader.load("./a.drake"), None);
+    assert_eq!(loader.load("b.drake"), None);
+
+    assert_eq!(
+        loader.insert(String::from("b.drake"), String::from("b = 1\n")),
+        None
+    );
+    assert_eq!(
+        loader.insert(String::from("a.drake"), String::from("a = 2\n")),
+        Some(String::from("a = 1\n"))
+    );
+    assert_eq!(loader.load("a.drake"), Some(String::from("a = 2\n")));
+    assert_eq!(loader.load("b.drake"), Some(String::from("b = 1\n")));
+
+    assert_eq!(loader.remove("a.drake"), Some(String::from("a = 2\n")));
+    assert_eq!(loader.remove("a.drake"), None);
+    assert_eq!(loader.load("a.drake"), None);
+    assert_eq!(loader, {
+        let mut loader = MemoryLoader::new();
+        loader.insert(String::from("b.drake"), String::from("b = 1\n"));
+        loader
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn fs() {
+    use super::FsLoader;
+
+    assert!(FsLoader.load("Cargo.toml").is_some());
+    assert_eq!(FsLoader.load("missing.drake"), None);
+}
@@ -1,27 +1,33 @@
 //! Processing modules
+mod data;
 mod parse;
+mod render;
 
 use alloc::string::String;
 use alloc::vec::Vec;
 use codespan_reporting::files::Files;
 use core::ops::Range;
-use drake_ir::interpret;
+use drake_ir::{export, interpret_with, Importer};
 use drake_types::ast::Statement;
 use drake_types::error::Error;
-use drake_types::ir::Ir;
+use drake_types::ir::{Expr, ExprKind, Ir};
 
 use crate::files::Source;
 pub use parse::Token;
 use parse::{parse, tokenize};
+pub use render::{FileType, Output};
 
 /// A struct contains partial (or full) information while processing a module
 #[derive(Debug, Clone, PartialEq)]
 pub struct Module {
     name: String,
     source: Source,
+    base: usize,
+    data: Option<FileType>,
     tokens: Option<Vec<Token>>,
     ast: Option<Vec<Statement<usize>>>,
     ir: Option<Ir<usize>>,
+    exports: Option<Expr<usize>>,
     errors: Vec<Error<usize>>,
 }
 
@@ -68,29 +74,45 @@ impl Module {
     /// Creates a new instance.
     #[inline]
     pub fn new(name: String, source: String) -> Self {
+        Self::with_base(name, source, 0)
+    }
+
+    /// Creates a new instance whose positions are offset by `base`.
+    ///
+    /// Modules in a runtime have distinct bases so that spans never overlap between modules.
+    #[inline]
+    pub fn with_base(name: String, source: String, base: usize) -> Self {
         Self {
             name,
             source: Source::new(source),
+            base,
+            data: None,
             tokens: None,
             ast: None,
             ir: None,
+            exports: None,
             errors: Vec::new(),
         }
     }
 
+    /// Creates a new instance of a data file in `filetype` like JSON, which is not tokenized nor
+    /// parsed, but only imported as a value by [`export`](Self::export).
+    #[inline]
+    pub fn with_data(name: String, source: String, base: usize, filetype: FileType) -> Self {
+        Self {
+            data: Some(filetype),
+            ..Self::with_base(name, source, base)
+        }
+    }
+
     /// Tokenizes the module and returns a reference of tokens.
     pub async fn tokenize(&mut self) -> &[Token] {
         if let Some(ref tokens) = self.tokens {
             return tokens.as_slice();
         }
 
-        let tokens = match tokenize(self.source.as_ref()).await {
-            Ok(tokens) => tokens,
-            Err(err) => {
-                self.errors.push(err);
-                Vec::new()
-            }
-        };
+        let (tokens, mut errors) = tokenize(self.source.as_ref(), self.base).await;
+        self.errors.append(&mut errors);
 
         self.tokens = Some(tokens);
         self.tokens.as_ref().unwrap()
@@ -104,33 +126,110 @@ impl Module {
             return ast.as_slice();
         }
 
-        let ast = match parse(self.tokenize().await).await {
-            Ok(ast) => ast,
-            Err(err) => {
-                self.errors.push(err);
-                Vec::new()
-            }
-        };
+        let (ast, mut errors) = parse(self.tokenize().await).await;
+        self.errors.append(&mut errors);
 
         self.ast = Some(ast);
         self.ast.as_ref().unwrap()
     }
 
     /// Interprets the module and returns a reference of IR.
+    ///
+    /// Note that this function also does parsing if it has not done yet. Imports are reported as
+    /// errors, use [`Runtime::evaluate`](crate::Runtime::evaluate) to import other files.
+    #[inline]
     pub async fn evaluate(&mut self) -> &Ir<usize> {
-        let (ir, mut errors) = interpret(self.parse().await);
+        self.evaluate_with(&()).await
+    }
+
+    /// Interprets the module importing other files by the importer, and returns a reference of IR.
+    ///
+    /// Note that this function also does parsing if it has not done yet.
+    pub async fn evaluate_with(&mut self, importer: &dyn Importer<usize>) -> &Ir<usize> {
+        if let Some(ref ir) = self.ir {
+            return ir;
+        }
+
+        let (ir, mut errors) = interpret_with(self.parse().await, importer);
 
         self.errors.append(&mut errors);
         self.ir = Some(ir);
         self.ir.as_ref().unwrap()
     }
 
+    /// Gets the value to be imported by other modules, which is the root table without local keys,
+    /// or the parsed value of a data file.
+    ///
+    /// Note that this function also does interpreting if it has not done yet.
+    pub async fn export(&mut self) -> &Expr<usize> {
+        if let Some(ref exports) = self.exports {
+            return exports;
+        }
+
+        let span = self.base..self.base + self.source.as_ref().len();
+        let exports = match self.data {
+            Some(filetype) => match data::parse(filetype, self.source.as_ref(), self.base) {
+                Ok(value) => value,
+                Err(err) => {
+                    self.errors.push(err);
+                    Expr {
+                        kind: ExprKind::Table(Default::default()),
+                        span,
+                    }
+                }
+            },
+            None => {
+                let (table, mut errors) = export(self.evaluate().await);
+                self.errors.append(&mut errors);
+                Expr {
+                    kind: ExprKind::Table(table),
+                    span,
+                }
+            }
+        };
+
+        self.exports = Some(exports);
+        self.exports.as_ref().unwrap()
+    }
+
+    /// Renders the module to `filetype`, or the file type specified by `@filetype` or inferred
+    /// from `@output` if `None` is passed.
+    ///
+    /// Note that this function also does interpreting if it has not done yet. Nothing is rendered
+    /// if any errors have occurred.
+    pub async fn render(&mut self, filetype: Option<FileType>) -> Option<Output> {
+        self.evaluate().await;
+        if !self.errors.is_empty() {
+            return None;
+        }
+
+        match render::render(self.ir.as_ref().unwrap(), filetype) {
+            Ok(output) => Some(output),
+            Err(err) => {
+                self.errors.push(err);
+                None
+            }
+        }
+    }
+
     /// Gets a reference for the name of the module.
     #[inline]
     pub fn get_name(&self) -> &str {
         &self.name
     }
 
+    /// Gets the offset of positions in the module.
+    #[inline]
+    pub fn get_base(&self) -> usize {
+        self.base
+    }
+
+    /// Gets the file type if the module is a data file.
+    #[inline]
+    pub fn get_data_type(&self) -> Option<FileType> {
+        self.data
+    }
+
     /// Gets a reference for the source code of the module.
     #[inline]
     pub fn get_source(&self) -> &str {
@@ -154,4 +253,10 @@ impl Module {
     pub fn get_ir(&self) -> Option<&Ir<usize>> {
         self.ir.as_ref()
     }
+
+    /// Gets a reference for errors occurred while processing the module.
+    #[inline]
+    pub fn get_errors(&self) -> &[Error<usize>] {
+        &self.errors
+    }
 }
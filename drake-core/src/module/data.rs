@@ -0,0 +1,26 @@
+mod json;
+#[cfg(feature = "std")]
+mod toml;
+#[cfg(feature = "std")]
+mod yaml;
+
+use drake_types::error::Error;
+use drake_types::ir::Expr;
+
+use super::FileType;
+
+/// Parses a data file to a value, whose spans are offset by `base`.
+pub fn parse(filetype: FileType, source: &str, base: usize) -> Result<Expr<usize>, Error<usize>> {
+    match filetype {
+        FileType::Json => json::parse(source, base),
+        #[cfg(feature = "std")]
+        FileType::Yaml => yaml::parse(source, base),
+        #[cfg(feature = "std")]
+        FileType::Toml => toml::parse(source, base),
+        #[cfg(not(feature = "std"))]
+        FileType::Yaml | FileType::Toml => Err(Error::NotSupported {
+            feature: "YAML and TOML files without the standard library",
+            span: base..base,
+        }),
+    }
+}
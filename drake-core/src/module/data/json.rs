@@ -0,0 +1,340 @@
+#[cfg(test)]
+mod tests;
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+use drake_types::ast::Literal;
+use drake_types::error::Error;
+use drake_types::ir::{Expr, ExprKind, Table};
+
+/// The maximum depth of nested arrays and objects
+const DEPTH_LIMIT: usize = 128;
+
+pub fn parse(source: &str, base: usize) -> Result<Expr<usize>, Error<usize>> {
+    let mut parser = Parser {
+        source,
+        pos: 0,
+        base,
+        depth: 0,
+    };
+    parser.whitespaces();
+    let value = parser.value()?;
+    parser.whitespaces();
+    if parser.pos < source.len() {
+        return Err(parser.error("end of file"));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    pos: usize,
+    base: usize,
+    /// The depth of arrays and objects being parsed
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.source.as_bytes().get(self.pos).copied()
+    }
+
+    fn span(&self, start: usize) -> Range<usize> {
+        self.base + start..self.base + self.pos
+    }
+
+    /// Makes an error at the current character.
+    fn error(&self, expects: &'static str) -> Error<usize> {
+        let len = self.source[self.pos..]
+            .chars()
+            .next()
+            .map_or(0, char::len_utf8);
+        Error::ParseError {
+            expects: expects.into(),
+            span: self.base + self.pos..self.base + self.pos + len,
+        }
+    }
+
+    fn whitespaces(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn symbol(&mut self, symbol: u8, expects: &'static str) -> Result<(), Error<usize>> {
+        if self.peek() == Some(symbol) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(expects))
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        if self.source[self.pos..].starts_with(keyword) {
+            self.pos += keyword.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn value(&mut self) -> Result<Expr<usize>, Error<usize>> {
+        let start = self.pos;
+        let kind = match self.peek() {
+            Some(b'{') => {
+                self.enter()?;
+                let table = self.object()?;
+                self.depth -= 1;
+                ExprKind::Table(table)
+            }
+            Some(b'[') => {
+                self.enter()?;
+                let arr = self.array()?;
+                self.depth -= 1;
+                ExprKind::Array(arr)
+            }
+            Some(b'"') => ExprKind::Literal(Literal::String(self.string()?)),
+            Some(b'-' | b'0'..=b'9') => ExprKind::Literal(self.number()?),
+            _ if self.keyword("true") => ExprKind::Literal(Literal::Boolean(true)),
+            _ if self.keyword("false") => ExprKind::Literal(Literal::Boolean(false)),
+            _ if self.keyword("null") => ExprKind::Literal(Literal::Null),
+            _ => return Err(self.error("a value")),
+        };
+        Ok(Expr {
+            kind,
+            span: self.span(start),
+        })
+    }
+
+    /// Starts an array or an object, failing if it is nested too deeply.
+    fn enter(&mut self) -> Result<(), Error<usize>> {
+        if self.depth >= DEPTH_LIMIT {
+            return Err(Error::InvalidData {
+                filetype: "JSON",
+                message: String::from("recursion limit exceeded"),
+                span: self.base + self.pos..self.base + self.pos + 1,
+            });
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn object(&mut self) -> Result<Table<Expr<usize>>, Error<usize>> {
+        self.pos += 1;
+        self.whitespaces();
+
+        let mut table = Table::new();
+        let mut keys = BTreeMap::<String, Range<usize>>::new();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(table);
+        }
+        loop {
+            let start = self.pos;
+            if self.peek() != Some(b'"') {
+                return Err(self.error("a string key"));
+            }
+            let key = self.string()?;
+            let span = self.span(start);
+            if let Some(existing) = keys.get(&key) {
+                return Err(Error::DuplicateKey {
+                    found: span,
+                    existing: Some(existing.clone()),
+                });
+            }
+
+            self.whitespaces();
+            self.symbol(b':', "`:`")?;
+            self.whitespaces();
+            let value = self.value()?;
+            table.global.insert(key.clone(), value);
+            keys.insert(key, span);
+
+            self.whitespaces();
+            match self.peek() {
+                Some(b',') => {
+                    self.pos += 1;
+                    self.whitespaces();
+                }
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(table);
+                }
+                _ => return Err(self.error("`,` or `}`")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Vec<Expr<usize>>, Error<usize>> {
+        self.pos += 1;
+        self.whitespaces();
+
+        let mut array = Vec::new();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(array);
+        }
+        loop {
+            array.push(self.value()?);
+            self.whitespaces();
+            match self.peek() {
+                Some(b',') => {
+                    self.pos += 1;
+                    self.whitespaces();
+                }
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(array);
+                }
+                _ => return Err(self.error("`,` or `]`")),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Error<usize>> {
+        self.pos += 1;
+
+        let mut string = String::new();
+        loop {
+            let c = match self.source[self.pos..].chars().next() {
+                Some(c) => c,
+                None => return Err(self.error("`\"`")),
+            };
+            match c {
+                '"' => {
+                    self.pos += 1;
+                    return Ok(string);
+                }
+                '\\' => {
+                    self.pos += 1;
+                    string.push(self.escape()?);
+                }
+                '\0'..='\x1f' => return Err(self.error("a character or `\"`")),
+                c => {
+                    self.pos += c.len_utf8();
+                    string.push(c);
+                }
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, Error<usize>> {
+        let c = match self.peek() {
+            Some(b'"') => '"',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\x08',
+            Some(b'f') => '\x0c',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                let start = self.pos - 1;
+                self.pos += 1;
+                let high = self.hex()?;
+                let code = if (0xd800..0xdc00).contains(&high) && self.keyword("\\u") {
+                    let low = self.hex()?;
+                    if !(0xdc00..0xe000).contains(&low) {
+                        return Err(Error::ParseError {
+                            expects: "a low surrogate".into(),
+                            span: self.span(self.pos - 6),
+                        });
+                    }
+                    0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+                } else {
+                    high
+                };
+                return char::from_u32(code).ok_or_else(|| Error::ParseError {
+                    expects: "a valid unicode escape".into(),
+                    span: self.span(start),
+                });
+            }
+            _ => return Err(self.error("an escape sequence")),
+        };
+        self.pos += 1;
+        Ok(c)
+    }
+
+    fn hex(&mut self) -> Result<u32, Error<usize>> {
+        let mut code = 0;
+        for _ in 0..4 {
+            match self.peek().and_then(|c| (c as char).to_digit(16)) {
+                Some(digit) => code = code * 16 + digit,
+                None => return Err(self.error("a hexadecimal digit")),
+            }
+            self.pos += 1;
+        }
+        Ok(code)
+    }
+
+    fn number(&mut self) -> Result<Literal, Error<usize>> {
+        let start = self.pos;
+        let negative = self.peek() == Some(b'-');
+        if negative {
+            self.pos += 1;
+        }
+
+        match self.peek() {
+            Some(b'0') => self.pos += 1,
+            Some(b'1'..=b'9') => self.digits(),
+            _ => return Err(self.error("a digit")),
+        }
+        let mut float = false;
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            self.digit()?;
+            float = true;
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            self.pos += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.pos += 1;
+            }
+            self.digit()?;
+            float = true;
+        }
+
+        let text = &self.source[start..self.pos];
+        // Integers out of range are treated as floats, like most JSON parsers.
+        if !float {
+            if let Ok(i) = text.parse::<u64>() {
+                return Ok(Literal::Integer(i));
+            }
+            match text.parse::<i64>() {
+                // `-0` is the same integer as `0`.
+                Ok(0) => return Ok(Literal::Integer(0)),
+                Ok(i) => return Ok(Literal::NegativeInteger(i)),
+                Err(_) => {}
+            }
+        }
+        // JSON has no infinities, so numbers too large for floats are rejected.
+        match text.parse::<f64>().unwrap() {
+            f if f.is_finite() => Ok(Literal::Float(f)),
+            _ => Err(Error::InvalidData {
+                filetype: "JSON",
+                message: String::from("number out of range"),
+                span: self.span(start),
+            }),
+        }
+    }
+
+    /// Consumes one or more digits.
+    fn digit(&mut self) -> Result<(), Error<usize>> {
+        match self.peek() {
+            Some(b'0'..=b'9') => {
+                self.digits();
+                Ok(())
+            }
+            _ => Err(self.error("a digit")),
+        }
+    }
+
+    fn digits(&mut self) {
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+    }
+}
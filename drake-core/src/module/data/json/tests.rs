@@ -0,0 +1,233 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+use drake_types::ast::Literal;
+use drake_types::error::Error;
+use drake_types::ir::{Expr, ExprKind, Table};
+
+use super::parse;
+
+fn lit(lit: Literal, span: Range<usize>) -> Expr<usize> {
+    Expr {
+        kind: ExprKind::Literal(lit),
+        span,
+    }
+}
+
+fn array(arr: Vec<Expr<usize>>, span: Range<usize>) -> Expr<usize> {
+    Expr {
+        kind: ExprKind::Array(arr),
+        span,
+    }
+}
+
+fn table(pairs: Vec<(&str, Expr<usize>)>, span: Range<usize>) -> Expr<usize> {
+    let mut table = Table::new();
+    for (key, value) in pairs {
+        table.global.insert(String::from(key), value);
+    }
+    Expr {
+        kind: ExprKind::Table(table),
+        span,
+    }
+}
+
+/// Parses the source, and returns only the kind of the value.
+fn kind(source: &str) -> ExprKind<usize> {
+    parse(source, 0).unwrap().kind
+}
+
+#[test]
+fn values() {
+    let cases = [
+        ("0", Literal::Integer(0)),
+        ("-0", Literal::Integer(0)),
+        ("-1", Literal::NegativeInteger(-1)),
+        ("18446744073709551615", Literal::Integer(u64::MAX)),
+        ("-9223372036854775808", Literal::NegativeInteger(i64::MIN)),
+        // Integers out of range are floats.
+        (
+            "18446744073709551616",
+            Literal::Float(18446744073709551616.0),
+        ),
+        ("-0.0", Literal::Float(-0.0)),
+        ("1.5e3", Literal::Float(1500.0)),
+        ("2E-1", Literal::Float(0.2)),
+        ("true", Literal::Boolean(true)),
+        ("false", Literal::Boolean(false)),
+        ("null", Literal::Null),
+        (
+            r#""a\"\\\/\b\f\n\r\t""#,
+            Literal::String(String::from("a\"\\/\x08\x0c\n\r\t")),
+        ),
+        (r#""é😀""#, Literal::String(String::from("é😀"))),
+    ];
+    for (source, lit) in cases {
+        assert_eq!(kind(source), ExprKind::Literal(lit), "{source}");
+    }
+}
+
+#[test]
+fn spans() {
+    // Spans are offset by the base.
+    assert_eq!(
+        parse(" {\"a\": [1, \"é\"], \"b\": {}}\n", 10),
+        Ok(table(
+            vec![
+                (
+                    "a",
+                    array(
+                        vec![
+                            lit(Literal::Integer(1), 18..19),
+                            lit(Literal::String(String::from("é")), 21..25),
+                        ],
+                        17..26,
+                    ),
+                ),
+                ("b", table(vec![], 33..35)),
+            ],
+            11..36,
+        ))
+    );
+}
+
+#[test]
+fn errors() {
+    let cases = [
+        (
+            "",
+            Error::ParseError {
+                expects: "a value".into(),
+                span: 10..10,
+            },
+        ),
+        (
+            "{\"a\": 1,}",
+            Error::ParseError {
+                expects: "a string key".into(),
+                span: 18..19,
+            },
+        ),
+        (
+            "{\"a\" 1}",
+            Error::ParseError {
+                expects: "`:`".into(),
+                span: 15..16,
+            },
+        ),
+        (
+            "[1 2]",
+            Error::ParseError {
+                expects: "`,` or `]`".into(),
+                span: 13..14,
+            },
+        ),
+        (
+            "1 2",
+            Error::ParseError {
+                expects: "end of file".into(),
+                span: 12..13,
+            },
+        ),
+        (
+            "01",
+            Error::ParseError {
+                expects: "end of file".into(),
+                span: 11..12,
+            },
+        ),
+        (
+            "1.",
+            Error::ParseError {
+                expects: "a digit".into(),
+                span: 12..12,
+            },
+        ),
+        (
+            "\"é",
+            Error::ParseError {
+                expects: "`\"`".into(),
+                span: 13..13,
+            },
+        ),
+        (
+            "\"\n\"",
+            Error::ParseError {
+                expects: "a character or `\"`".into(),
+                span: 11..12,
+            },
+        ),
+        (
+            r#""\x""#,
+            Error::ParseError {
+                expects: "an escape sequence".into(),
+                span: 12..13,
+            },
+        ),
+        (
+            r#""\ud800\u0041""#,
+            Error::ParseError {
+                expects: "a low surrogate".into(),
+                span: 17..23,
+            },
+        ),
+        (
+            r#""\ud800A""#,
+            Error::ParseError {
+                expects: "a valid unicode escape".into(),
+                span: 11..17,
+            },
+        ),
+        (
+            r#""\udc00""#,
+            Error::ParseError {
+                expects: "a valid unicode escape".into(),
+                span: 11..17,
+            },
+        ),
+        (
+            "[1e400]",
+            Error::InvalidData {
+                filetype: "JSON",
+                message: String::from("number out of range"),
+                span: 11..16,
+            },
+        ),
+        (
+            "-1E999",
+            Error::InvalidData {
+                filetype: "JSON",
+                message: String::from("number out of range"),
+                span: 10..16,
+            },
+        ),
+        (
+            "{\"a\": 1, \"a\": 2}",
+            Error::DuplicateKey {
+                found: 19..22,
+                existing: Some(11..14),
+            },
+        ),
+    ];
+    for (source, err) in cases {
+        assert_eq!(parse(source, 10), Err(err), "{source}");
+    }
+}
+
+#[test]
+fn depth() {
+    let source = "[".repeat(128) + &"]".repeat(128);
+    assert!(parse(&source, 0).is_ok());
+
+    // Deeply nested values are rejected before overflowing the stack.
+    let source = "[{\"a\": ".repeat(5000);
+    assert_eq!(
+        parse(&source, 10),
+        Err(Error::InvalidData {
+            filetype: "JSON",
+            message: String::from("recursion limit exceeded"),
+            span: 10 + 64 * 7..10 + 64 * 7 + 1,
+        })
+    );
+}
@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests;
+
+use alloc::string::{String, ToString};
+use core::ops::Range;
+use drake_types::ast::Literal;
+use drake_types::error::Error;
+use drake_types::ir::{Expr, ExprKind, Table};
+use toml_edit::{ImDocument, InlineTable, Item, Value};
+
+pub fn parse(source: &str, base: usize) -> Result<Expr<usize>, Error<usize>> {
+    let doc = ImDocument::parse(source).map_err(|err| Error::InvalidData {
+        filetype: "TOML",
+        message: err.message().trim_end().replace('\n', ", "),
+        span: offset(err.span(), base..base, base),
+    })?;
+
+    Ok(Expr {
+        kind: ExprKind::Table(table(doc.as_table(), base)),
+        span: base..base + source.len(),
+    })
+}
+
+/// Gets the span in the runtime, or the fallback if spans are missing like implicit tables.
+fn offset(span: Option<Range<usize>>, fallback: Range<usize>, base: usize) -> Range<usize> {
+    match span {
+        Some(span) => base + span.start..base + span.end,
+        None => fallback,
+    }
+}
+
+fn table(tab: &toml_edit::Table, base: usize) -> Table<Expr<usize>> {
+    let mut res = Table::new();
+    for (key, item) in tab.iter() {
+        let fallback = match tab.get_key_value(key) {
+            Some((key, _)) => offset(key.span(), base..base, base),
+            None => base..base,
+        };
+        if let Some(expr) = self::item(item, fallback, base) {
+            res.global.insert(String::from(key), expr);
+        }
+    }
+    res
+}
+
+fn inline_table(tab: &InlineTable, base: usize) -> Table<Expr<usize>> {
+    let mut res = Table::new();
+    for (key, val) in tab.iter() {
+        let fallback = match tab.get_key_value(key) {
+            Some((key, _)) => offset(key.span(), base..base, base),
+            None => base..base,
+        };
+        res.global
+            .insert(String::from(key), value(val, fallback, base));
+    }
+    res
+}
+
+fn item(item: &Item, fallback: Range<usize>, base: usize) -> Option<Expr<usize>> {
+    let expr = match item {
+        Item::None => return None,
+        Item::Value(val) => value(val, fallback, base),
+        Item::Table(tab) => Expr {
+            kind: ExprKind::Table(table(tab, base)),
+            span: offset(tab.span(), fallback, base),
+        },
+        Item::ArrayOfTables(arr) => Expr {
+            kind: ExprKind::Array(
+                arr.iter()
+                    .map(|tab| Expr {
+                        kind: ExprKind::Table(table(tab, base)),
+                        span: offset(tab.span(), fallback.clone(), base),
+                    })
+                    .collect(),
+            ),
+            span: offset(arr.span(), fallback, base),
+        },
+    };
+    Some(expr)
+}
+
+fn value(val: &Value, fallback: Range<usize>, base: usize) -> Expr<usize> {
+    let kind = match val {
+        Value::String(s) => ExprKind::Literal(Literal::String(s.value().clone())),
+        Value::Integer(i) => ExprKind::Literal(match *i.value() {
+            i if i < 0 => Literal::NegativeInteger(i),
+            i => Literal::Integer(i as u64),
+        }),
+        Value::Float(f) => ExprKind::Literal(Literal::Float(*f.value())),
+        Value::Boolean(b) => ExprKind::Literal(Literal::Boolean(*b.value())),
+        // Drake has no date-time types, so they are imported as strings.
+        Value::Datetime(dt) => ExprKind::Literal(Literal::String(dt.value().to_string())),
+        Value::Array(arr) => ExprKind::Array(
+            arr.iter()
+                .map(|val| value(val, fallback.clone(), base))
+                .collect(),
+        ),
+        Value::InlineTable(tab) => ExprKind::Table(inline_table(tab, base)),
+    };
+    Expr {
+        kind,
+        span: offset(val.span(), fallback, base),
+    }
+}
@@ -0,0 +1,168 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+use drake_types::ast::Literal;
+use drake_types::error::Error;
+use drake_types::ir::{Expr, ExprKind, Table};
+
+use super::parse;
+
+fn lit(lit: Literal, span: Range<usize>) -> Expr<usize> {
+    Expr {
+        kind: ExprKind::Literal(lit),
+        span,
+    }
+}
+
+fn array(arr: Vec<Expr<usize>>, span: Range<usize>) -> Expr<usize> {
+    Expr {
+        kind: ExprKind::Array(arr),
+        span,
+    }
+}
+
+fn table(pairs: Vec<(&str, Expr<usize>)>, span: Range<usize>) -> Expr<usize> {
+    let mut table = Table::new();
+    for (key, value) in pairs {
+        table.global.insert(String::from(key), value);
+    }
+    Expr {
+        kind: ExprKind::Table(table),
+        span,
+    }
+}
+
+#[test]
+fn values() {
+    let cases = [
+        ("0", Literal::Integer(0)),
+        ("-0", Literal::Integer(0)),
+        ("-1", Literal::NegativeInteger(-1)),
+        ("0xff", Literal::Integer(255)),
+        ("9223372036854775807", Literal::Integer(i64::MAX as u64)),
+        ("-9223372036854775808", Literal::NegativeInteger(i64::MIN)),
+        ("1.5", Literal::Float(1.5)),
+        ("-inf", Literal::Float(f64::NEG_INFINITY)),
+        ("true", Literal::Boolean(true)),
+        ("'a\\n'", Literal::String(String::from("a\\n"))),
+        ("\"a\\n\"", Literal::String(String::from("a\n"))),
+        // Date-times are strings.
+        (
+            "1979-05-27T07:32:00Z",
+            Literal::String(String::from("1979-05-27T07:32:00Z")),
+        ),
+    ];
+    for (source, lit) in cases {
+        let value = parse(&alloc::format!("a = {source}\n"), 0).map(|expr| match expr.kind {
+            ExprKind::Table(mut table) => table.global.remove("a").unwrap().kind,
+            kind => kind,
+        });
+        assert_eq!(value, Ok(ExprKind::Literal(lit)), "{source}");
+    }
+}
+
+#[test]
+fn spans() {
+    let source = "a = 1
+[t]
+b = [2, \"x\"]
+c = { d = -0 }
+[[arr]]
+e = true
+[[arr]]
+[x.y]
+f.g = 1979-05-27T07:32:00Z
+";
+    // Implicit tables are reported at their keys.
+    assert_eq!(
+        parse(source, 10),
+        Ok(table(
+            vec![
+                ("a", lit(Literal::Integer(1), 14..15)),
+                (
+                    "t",
+                    table(
+                        vec![
+                            (
+                                "b",
+                                array(
+                                    vec![
+                                        lit(Literal::Integer(2), 25..26),
+                                        lit(Literal::String(String::from("x")), 28..31),
+                                    ],
+                                    24..32,
+                                ),
+                            ),
+                            (
+                                "c",
+                                table(vec![("d", lit(Literal::Integer(0), 43..45))], 37..47),
+                            ),
+                        ],
+                        16..47,
+                    ),
+                ),
+                (
+                    "arr",
+                    array(
+                        vec![
+                            table(vec![("e", lit(Literal::Boolean(true), 60..64))], 48..64),
+                            table(vec![], 65..72),
+                        ],
+                        48..72,
+                    ),
+                ),
+                (
+                    "x",
+                    table(
+                        vec![(
+                            "y",
+                            table(
+                                vec![(
+                                    "f",
+                                    table(
+                                        vec![(
+                                            "g",
+                                            lit(
+                                                Literal::String(String::from(
+                                                    "1979-05-27T07:32:00Z"
+                                                )),
+                                                85..105,
+                                            ),
+                                        )],
+                                        79..80,
+                                    ),
+                                )],
+                                73..105,
+                            ),
+                        )],
+                        74..75,
+                    ),
+                ),
+            ],
+            10..106,
+        ))
+    );
+}
+
+#[test]
+fn errors() {
+    let cases = [
+        ("a = 1\na = 2\n", "duplicate key", 16..17),
+        ("[t]\n[t]\n", "duplicate key", 14..15),
+        ("a = \n", "", 14..15),
+        ("a = 1 2\n", "", 16..17),
+        ("a = \"x", "", 16..16),
+        ("a = 18446744073709551615\n", "", 14..15),
+    ];
+    for (source, message, span) in cases {
+        match parse(source, 10) {
+            Err(Error::InvalidData {
+                filetype: "TOML",
+                message: ref msg,
+                span: ref found,
+            }) if msg.contains(message) && *found == span => {}
+            res => panic!("{source:?}: {res:?}"),
+        }
+    }
+}
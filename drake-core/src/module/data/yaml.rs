@@ -0,0 +1,292 @@
+#[cfg(test)]
+mod tests;
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+use core::str::Chars;
+use drake_types::ast::Literal;
+use drake_types::error::Error;
+use drake_types::ir::{Expr, ExprKind, Table};
+use yaml_rust2::parser::Parser as YamlParser;
+use yaml_rust2::scanner::TScalarStyle;
+use yaml_rust2::{Event, Yaml};
+
+pub fn parse(source: &str, base: usize) -> Result<Expr<usize>, Error<usize>> {
+    // Markers of events are positions on characters.
+    let offsets = source
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain(core::iter::once(source.len()))
+        .collect();
+    let mut parser = Parser {
+        parser: YamlParser::new_from_str(source),
+        source,
+        offsets,
+        base,
+        flow: 0,
+        anchors: BTreeMap::new(),
+    };
+
+    // Only one document is allowed in a file.
+    parser.next()?;
+    let (event, pos) = parser.next()?;
+    let value = match event {
+        Event::StreamEnd => {
+            return Ok(Expr {
+                kind: ExprKind::Table(Table::new()),
+                span: base..base + source.len(),
+            })
+        }
+        Event::DocumentStart => {
+            let (event, pos) = parser.next()?;
+            parser.node(event, pos)?
+        }
+        _ => return Err(parser.unexpected(pos)),
+    };
+    parser.next()?;
+    match parser.next()? {
+        (Event::StreamEnd, _) => Ok(value),
+        (_, pos) => Err(Error::NotSupported {
+            feature: "multiple YAML documents",
+            span: base + pos..base + pos,
+        }),
+    }
+}
+
+struct Parser<'a> {
+    parser: YamlParser<Chars<'a>>,
+    source: &'a str,
+    offsets: Vec<usize>,
+    base: usize,
+    /// The depth of flow collections like `[a, b]`
+    flow: usize,
+    anchors: BTreeMap<usize, Expr<usize>>,
+}
+
+impl Parser<'_> {
+    /// Gets the next event and its byte offset.
+    fn next(&mut self) -> Result<(Event, usize), Error<usize>> {
+        match self.parser.next_token() {
+            Ok((event, marker)) => Ok((event, self.offsets[marker.index()])),
+            Err(err) => {
+                let pos = self.base + self.offsets[err.marker().index()];
+                Err(Error::InvalidData {
+                    filetype: "YAML",
+                    message: String::from(err.info()),
+                    span: pos..pos,
+                })
+            }
+        }
+    }
+
+    fn unexpected(&self, pos: usize) -> Error<usize> {
+        Error::InvalidData {
+            filetype: "YAML",
+            message: String::from("unexpected event"),
+            span: self.base + pos..self.base + pos,
+        }
+    }
+
+    fn node(&mut self, event: Event, mut start: usize) -> Result<Expr<usize>, Error<usize>> {
+        let (kind, end, anchor) = match event {
+            Event::Scalar(value, style, anchor, tag) => {
+                self.tag(tag.is_some(), start)?;
+                let end = self.scalar_end(start, style);
+                (scalar(value, style), end, anchor)
+            }
+            Event::SequenceStart(anchor, tag) => {
+                self.tag(tag.is_some(), start)?;
+                let flow = self.enter(start);
+                let mut arr = Vec::new();
+                let end = loop {
+                    match self.next()? {
+                        (Event::SequenceEnd, pos) => break self.collection_end(pos, flow),
+                        (event, pos) => arr.push(self.node(event, pos)?),
+                    }
+                };
+                (ExprKind::Array(arr), end, anchor)
+            }
+            Event::MappingStart(anchor, tag) => {
+                self.tag(tag.is_some(), start)?;
+                let flow = self.enter(start);
+                let mut table = Table::new();
+                let mut keys = BTreeMap::<String, Range<usize>>::new();
+                let end = loop {
+                    let (key, span) = match self.next()? {
+                        (Event::MappingEnd, pos) => break self.collection_end(pos, flow),
+                        (Event::Scalar(key, style, _, _), pos) => {
+                            // Block mappings are marked at the end of the first key.
+                            if !flow {
+                                start = start.min(pos);
+                            }
+                            let span = self.base + pos..self.base + self.scalar_end(pos, style);
+                            (key, span)
+                        }
+                        (_, pos) => {
+                            return Err(Error::TypeMismatch {
+                                expected: "a scalar key",
+                                span: self.base + pos..self.base + pos,
+                            })
+                        }
+                    };
+                    if let Some(existing) = keys.get(&key) {
+                        return Err(Error::DuplicateKey {
+                            found: span,
+                            existing: Some(existing.clone()),
+                        });
+                    }
+
+                    let (event, pos) = self.next()?;
+                    table.global.insert(key.clone(), self.node(event, pos)?);
+                    keys.insert(key, span);
+                };
+                (ExprKind::Table(table), end, anchor)
+            }
+            Event::Alias(anchor) => {
+                return self
+                    .anchors
+                    .get(&anchor)
+                    .cloned()
+                    .ok_or_else(|| self.unexpected(start))
+            }
+            _ => return Err(self.unexpected(start)),
+        };
+
+        let expr = Expr {
+            kind,
+            span: self.base + start..self.base + end,
+        };
+        if anchor != 0 {
+            self.anchors.insert(anchor, expr.clone());
+        }
+        Ok(expr)
+    }
+
+    fn tag(&self, tagged: bool, pos: usize) -> Result<(), Error<usize>> {
+        if tagged {
+            Err(Error::NotSupported {
+                feature: "YAML tags",
+                span: self.base + pos..self.base + pos,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Starts a collection, and returns whether it is a flow collection.
+    fn enter(&mut self, start: usize) -> bool {
+        let flow = matches!(self.source.as_bytes().get(start), Some(b'[' | b'{'));
+        if flow {
+            self.flow += 1;
+        }
+        flow
+    }
+
+    /// Ends a collection, and returns the end of its span.
+    fn collection_end(&mut self, pos: usize, flow: bool) -> usize {
+        if flow {
+            self.flow -= 1;
+            pos + 1
+        } else {
+            // Block collections end at the next token.
+            self.source[..pos].trim_end().len()
+        }
+    }
+
+    /// Finds the end of a scalar starting at `start`.
+    fn scalar_end(&self, start: usize, style: TScalarStyle) -> usize {
+        let rest = &self.source[start..];
+        let len = match style {
+            TScalarStyle::SingleQuoted => {
+                let mut chars = rest.char_indices().skip(1).peekable();
+                loop {
+                    match chars.next() {
+                        Some((_, '\'')) if chars.peek().map(|(_, c)| *c) == Some('\'') => {
+                            chars.next();
+                        }
+                        Some((i, '\'')) => break i + 1,
+                        Some(_) => {}
+                        None => break rest.len(),
+                    }
+                }
+            }
+            TScalarStyle::DoubleQuoted => {
+                let mut chars = rest.char_indices().skip(1);
+                loop {
+                    match chars.next() {
+                        Some((_, '\\')) => {
+                            chars.next();
+                        }
+                        Some((i, '"')) => break i + 1,
+                        Some(_) => {}
+                        None => break rest.len(),
+                    }
+                }
+            }
+            // Block scalars are reported at their first lines.
+            TScalarStyle::Literal | TScalarStyle::Folded => rest.find('\n').unwrap_or(rest.len()),
+            TScalarStyle::Plain => {
+                let mut end = rest.len();
+                let mut prev = ' ';
+                for (i, c) in rest.char_indices() {
+                    let next = rest[i + c.len_utf8()..].chars().next();
+                    let stop = match c {
+                        '\n' | '\r' => true,
+                        ':' => next.is_none_or(|c| {
+                            c.is_whitespace() || (self.flow > 0 && ",[]{}".contains(c))
+                        }),
+                        '#' => prev.is_whitespace(),
+                        ',' | '[' | ']' | '{' | '}' => self.flow > 0,
+                        _ => false,
+                    };
+                    if stop {
+                        end = i;
+                        break;
+                    }
+                    prev = c;
+                }
+                rest[..end].trim_end().len()
+            }
+        };
+        start + len
+    }
+}
+
+fn scalar(value: String, style: TScalarStyle) -> ExprKind<usize> {
+    // Only plain scalars are resolved to non-string values.
+    if style != TScalarStyle::Plain {
+        return ExprKind::Literal(Literal::String(value));
+    }
+
+    // `Yaml::Integer` is `i64`, so larger integers are parsed first.
+    if let Some(i) = unsigned(&value) {
+        return ExprKind::Literal(Literal::Integer(i));
+    }
+    let lit = match Yaml::from_str(&value) {
+        _ if value.is_empty() => Literal::Null,
+        Yaml::Integer(i) if i < 0 => Literal::NegativeInteger(i),
+        Yaml::Integer(i) => Literal::Integer(i as u64),
+        real @ Yaml::Real(_) => match real.as_f64() {
+            Some(f) => Literal::Float(f),
+            None => Literal::String(value),
+        },
+        Yaml::Boolean(b) => Literal::Boolean(b),
+        Yaml::Null => Literal::Null,
+        _ => Literal::String(value),
+    };
+    ExprKind::Literal(lit)
+}
+
+/// Parses an unsigned integer in the same formats as `Yaml::from_str`.
+fn unsigned(value: &str) -> Option<u64> {
+    if let Some(hex) = value.strip_prefix("0x") {
+        u64::from_str_radix(hex, 16).ok()
+    } else if let Some(oct) = value.strip_prefix("0o") {
+        u64::from_str_radix(oct, 8).ok()
+    } else {
+        // A leading `+` is also accepted.
+        value.parse().ok()
+    }
+}
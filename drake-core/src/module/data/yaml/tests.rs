@@ -0,0 +1,163 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+use drake_types::ast::Literal;
+use drake_types::error::Error;
+use drake_types::ir::{Expr, ExprKind, Table};
+
+use super::parse;
+
+fn lit(lit: Literal, span: Range<usize>) -> Expr<usize> {
+    Expr {
+        kind: ExprKind::Literal(lit),
+        span,
+    }
+}
+
+fn array(arr: Vec<Expr<usize>>, span: Range<usize>) -> Expr<usize> {
+    Expr {
+        kind: ExprKind::Array(arr),
+        span,
+    }
+}
+
+fn table(pairs: Vec<(&str, Expr<usize>)>, span: Range<usize>) -> Expr<usize> {
+    let mut table = Table::new();
+    for (key, value) in pairs {
+        table.global.insert(String::from(key), value);
+    }
+    Expr {
+        kind: ExprKind::Table(table),
+        span,
+    }
+}
+
+#[test]
+fn values() {
+    let cases = [
+        ("0", Literal::Integer(0)),
+        ("-0", Literal::Integer(0)),
+        ("-1", Literal::NegativeInteger(-1)),
+        ("+5", Literal::Integer(5)),
+        ("0o17", Literal::Integer(15)),
+        ("0xff", Literal::Integer(255)),
+        // Integers larger than `i64` are kept as integers.
+        ("18446744073709551615", Literal::Integer(u64::MAX)),
+        ("0xffffffffffffffff", Literal::Integer(u64::MAX)),
+        ("-9223372036854775808", Literal::NegativeInteger(i64::MIN)),
+        (
+            "18446744073709551616",
+            Literal::Float(18446744073709551616.0),
+        ),
+        ("1.5", Literal::Float(1.5)),
+        ("-.inf", Literal::Float(f64::NEG_INFINITY)),
+        ("", Literal::Null),
+        ("~", Literal::Null),
+        ("null", Literal::Null),
+        ("True", Literal::Boolean(true)),
+        ("yes", Literal::String(String::from("yes"))),
+        // Quoted scalars are always strings.
+        ("'1'", Literal::String(String::from("1"))),
+        ("\"true\"", Literal::String(String::from("true"))),
+    ];
+    for (source, lit) in cases {
+        let value = parse(&alloc::format!("a: {source}\n"), 0).map(|expr| match expr.kind {
+            ExprKind::Table(mut table) => table.global.remove("a").unwrap().kind,
+            kind => kind,
+        });
+        assert_eq!(value, Ok(ExprKind::Literal(lit)), "{source}");
+    }
+}
+
+#[test]
+fn spans() {
+    let source = "a: 1
+b: [2, 'x', \"y\"]
+c:
+  - d: -0
+  - 18446744073709551615
+e: &x {f: null}
+g: *x
+h: |
+  text
+";
+    let e = table(vec![("f", lit(Literal::Null, 80..84))], 76..85);
+    assert_eq!(
+        parse(source, 10),
+        Ok(table(
+            vec![
+                ("a", lit(Literal::Integer(1), 13..14)),
+                (
+                    "b",
+                    array(
+                        vec![
+                            lit(Literal::Integer(2), 19..20),
+                            lit(Literal::String(String::from("x")), 22..25),
+                            lit(Literal::String(String::from("y")), 27..30),
+                        ],
+                        18..31,
+                    ),
+                ),
+                (
+                    "c",
+                    array(
+                        vec![
+                            table(vec![("d", lit(Literal::Integer(0), 42..44))], 39..44),
+                            lit(Literal::Integer(u64::MAX), 49..69),
+                        ],
+                        37..69,
+                    ),
+                ),
+                // Aliases are copies of anchored values.
+                ("e", e.clone()),
+                ("g", e),
+                ("h", lit(Literal::String(String::from("text\n")), 99..103)),
+            ],
+            10..103,
+        ))
+    );
+}
+
+#[test]
+fn errors() {
+    assert_eq!(
+        parse("a: 1\na: 2\n", 10),
+        Err(Error::DuplicateKey {
+            found: 15..16,
+            existing: Some(10..11),
+        })
+    );
+    assert_eq!(
+        parse("!!str a\n", 10),
+        Err(Error::NotSupported {
+            feature: "YAML tags",
+            span: 16..16,
+        })
+    );
+    assert_eq!(
+        parse("a\n---\nb\n", 10),
+        Err(Error::NotSupported {
+            feature: "multiple YAML documents",
+            span: 12..12,
+        })
+    );
+    assert_eq!(
+        parse("[a]: 1\n", 10),
+        Err(Error::TypeMismatch {
+            expected: "a scalar key",
+            span: 10..10,
+        })
+    );
+
+    for (source, span) in [("a: [1\n", 16..16), ("a: 'x\n", 13..13)] {
+        match parse(source, 10) {
+            Err(Error::InvalidData {
+                filetype: "YAML",
+                span: ref found,
+                ..
+            }) if *found == span => {}
+            res => panic!("{source:?}: {res:?}"),
+        }
+    }
+}
@@ -1,3 +1,6 @@
+#[cfg(test)]
+mod tests;
+
 use alloc::vec::Vec;
 use core::convert::Infallible;
 use core::ops::Range;
@@ -5,8 +8,8 @@ use core::pin::Pin;
 use core::task::{Context, Poll};
 use drake_types::ast::Statement;
 use drake_types::error::Error;
-use drake_types::token::Token as TokenKind;
-use futures_util::{Stream, TryStreamExt};
+use drake_types::token::{Symbol, Token as TokenKind};
+use futures_util::Stream;
 use pin_project_lite::pin_project;
 use somen::prelude::*;
 use somen::stream::{Rewind, SliceStream};
@@ -17,29 +20,118 @@ pub struct Token {
     pub span: Range<usize>,
 }
 
-pub async fn tokenize<T: AsRef<[u8]> + ?Sized>(source: &T) -> Result<Vec<Token>, Error<usize>> {
-    let mut bytes = stream::from_slice(source.as_ref());
-    let mut decoder = somen_decode::utf8().repeat(..).complete();
-    let mut input = decoder.parse_iterable(&mut bytes);
-    let mut lexer = drake_lexer::token()
-        .with_position()
-        .map(|(kind, span)| Token { kind, span })
-        .repeat(..);
+pub async fn tokenize(source: &str, base: usize) -> (Vec<Token>, Vec<Error<usize>>) {
+    // Lexing on characters, and converting positions to byte offsets.
+    let chars = source.chars().collect::<Vec<_>>();
+    let offsets = source
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain(core::iter::once(source.len()))
+        .collect::<Vec<_>>();
+    // Errors at the end of file are reported one character past it.
+    let offset = |span: Range<usize>| {
+        base + offsets[span.start.min(chars.len())]..base + offsets[span.end.min(chars.len())]
+    };
+
+    let mut input = stream::from_slice(&chars);
+    let mut lexer = drake_lexer::token().with_position();
+    let mut unknown = drake_lexer::unknown().with_position();
+    let mut tokens = Vec::new();
+    let mut errors = Vec::new();
+
+    // Skipping invalid sequences as unknown tokens to report as many errors as possible.
+    while input.position() < chars.len() {
+        let start = Pin::new(&mut input).mark().unwrap();
+        let (kind, span) = match lexer.parse(&mut input).await {
+            Ok(token) => {
+                Pin::new(&mut input).drop_marker(start).unwrap();
+                token
+            }
+            Err(err) => {
+                errors.push(match Error::from(err) {
+                    Error::ParseError { expects, span } => Error::ParseError {
+                        expects,
+                        span: offset(span),
+                    },
+                    err => err,
+                });
+                Pin::new(&mut input).rewind(start).unwrap();
+                // `unknown` consumes at least one character.
+                let ((), span) = unknown.parse(&mut input).await.unwrap();
+                (TokenKind::Unknown, span)
+            }
+        };
+        tokens.push(Token {
+            kind,
+            span: offset(span),
+        });
+    }
 
-    Ok(lexer.parse_iterable(&mut input).try_collect().await?)
+    (tokens, errors)
 }
 
-pub async fn parse(tokens: &[Token]) -> Result<Vec<Statement<usize>>, Error<usize>> {
+pub async fn parse(tokens: &[Token]) -> (Vec<Statement<usize>>, Vec<Error<usize>>) {
     let mut input = TokenStream::from(tokens);
-    let mut parser = drake_parser::statement::statement().repeat(..);
+    let mut parser = drake_parser::statement::line();
+    let mut statements = Vec::new();
+    let mut errors = Vec::new();
 
-    Ok(parser.parse_iterable(&mut input).try_collect().await?)
+    // Parsing line by line, skipping broken statements to report as many errors as possible.
+    while input.index() < tokens.len() {
+        let start = input.index();
+        match parser.parse(&mut input).await {
+            Ok(Some(statement)) => statements.push(statement),
+            Ok(None) => {}
+            Err(err) => {
+                let end = start + resync(&tokens[start..]);
+                // Unknown tokens have already been reported by the lexer.
+                if tokens[start..end]
+                    .iter()
+                    .all(|token| token.kind != TokenKind::Unknown)
+                {
+                    errors.push(err.into());
+                }
+                input.seek(end);
+            }
+        }
+    }
+
+    (statements, errors)
+}
+
+/// Finds the beginning of the next statement from the beginning of a broken statement.
+///
+/// It is just after a newline outside of brackets, or a newline followed by a table header.
+fn resync(tokens: &[Token]) -> usize {
+    let mut depth = 0isize;
+    let mut continued = false;
+    for (i, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TokenKind::Whitespaces | TokenKind::Comment(_) => continue,
+            TokenKind::Newline if continued => {}
+            TokenKind::Newline
+                if depth <= 0
+                    || matches!(
+                        tokens.get(i + 1).map(|token| &token.kind),
+                        Some(TokenKind::Symbol(Symbol::OpenBracket))
+                    ) =>
+            {
+                return i + 1
+            }
+            TokenKind::Symbol(Symbol::OpenBracket | Symbol::OpenBrace) => depth += 1,
+            TokenKind::Symbol(Symbol::CloseBracket | Symbol::CloseBrace) => depth -= 1,
+            _ => {}
+        }
+        continued = token.kind == TokenKind::Symbol(Symbol::BackSlash);
+    }
+    tokens.len()
 }
 
 pin_project! {
     struct TokenStream<'a> {
         #[pin]
         inner: SliceStream<'a, Token>,
+        slice: &'a [Token],
         cur: usize,
     }
 }
@@ -49,11 +141,29 @@ impl<'a> From<&'a [Token]> for TokenStream<'a> {
     fn from(slice: &'a [Token]) -> Self {
         Self {
             inner: stream::from_slice(slice),
+            slice,
             cur: 0,
         }
     }
 }
 
+impl TokenStream<'_> {
+    /// Gets the index of the next token.
+    #[inline]
+    fn index(&self) -> usize {
+        self.inner.position()
+    }
+
+    /// Moves to the token at the index.
+    fn seek(&mut self, index: usize) {
+        self.cur = match index {
+            0 => 0,
+            _ => self.slice[index - 1].span.end,
+        };
+        Pin::new(&mut self.inner).rewind(index).unwrap();
+    }
+}
+
 impl Stream for TokenStream<'_> {
     type Item = Result<TokenKind, Infallible>;
 
@@ -81,20 +191,23 @@ impl Positioned for TokenStream<'_> {
 }
 
 impl Rewind for TokenStream<'_> {
-    type Marker = usize;
+    type Marker = (usize, usize);
 
     #[inline]
     fn mark(self: Pin<&mut Self>) -> Result<Self::Marker, Self::Error> {
-        self.project().inner.mark()
+        let this = self.project();
+        Ok((this.inner.mark()?, *this.cur))
     }
 
     #[inline]
-    fn rewind(self: Pin<&mut Self>, marker: Self::Marker) -> Result<(), Self::Error> {
-        self.project().inner.rewind(marker)
+    fn rewind(self: Pin<&mut Self>, (marker, cur): Self::Marker) -> Result<(), Self::Error> {
+        let this = self.project();
+        *this.cur = cur;
+        this.inner.rewind(marker)
     }
 
     #[inline]
-    fn drop_marker(self: Pin<&mut Self>, marker: Self::Marker) -> Result<(), Self::Error> {
+    fn drop_marker(self: Pin<&mut Self>, (marker, _): Self::Marker) -> Result<(), Self::Error> {
         self.project().inner.drop_marker(marker)
     }
 }
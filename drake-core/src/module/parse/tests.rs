@@ -0,0 +1,152 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+use core::pin::Pin;
+use drake_types::ast::{PatternKind, Statement, StatementKind};
+use drake_types::error::Error;
+use drake_types::token::{Literal, Radix, StringKind, Symbol, Token as TokenKind};
+use futures_executor::block_on;
+use futures_util::StreamExt;
+use somen::prelude::*;
+use somen::stream::Rewind;
+
+use super::{parse, tokenize, Token, TokenStream};
+
+/// Gets names of keys bound or declared by statements.
+fn keys(ast: &[Statement<usize>]) -> Vec<&str> {
+    ast.iter()
+        .filter_map(|stmt| match stmt.kind {
+            StatementKind::ValueBinding(ref pat, _) | StatementKind::TableHeader(_, ref pat, _) => {
+                match pat.kind {
+                    PatternKind::Key(ref key) => Some(key.name.as_str()),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Gets spans of errors.
+fn spans(errors: &[Error<usize>]) -> Vec<Range<usize>> {
+    errors
+        .iter()
+        .filter_map(|err| err.span().cloned())
+        .collect()
+}
+
+#[test]
+fn byte_offsets() {
+    let (tokens, errors) = block_on(tokenize("\"あ\" = 1\n", 10));
+    assert_eq!(errors, Vec::new());
+    assert_eq!(
+        tokens,
+        [
+            Token {
+                kind: TokenKind::Literal(Literal::String(String::from("あ"), StringKind::Normal)),
+                span: 10..15,
+            },
+            Token {
+                kind: TokenKind::Whitespaces,
+                span: 15..16,
+            },
+            Token {
+                kind: TokenKind::Symbol(Symbol::Assign),
+                span: 16..17,
+            },
+            Token {
+                kind: TokenKind::Whitespaces,
+                span: 17..18,
+            },
+            Token {
+                kind: TokenKind::Literal(Literal::Integer(1, Radix::Decimal)),
+                span: 18..19,
+            },
+            Token {
+                kind: TokenKind::Newline,
+                span: 19..20,
+            },
+        ]
+    );
+}
+
+#[test]
+fn error_offsets() {
+    let (tokens, errors) = block_on(tokenize("\"あ\" = 0x\n", 10));
+    assert_eq!(tokens[4].kind, TokenKind::Unknown);
+    assert_eq!(tokens[4].span, 18..20);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].span(), Some(&(20..21)));
+}
+
+#[test]
+fn rewind() {
+    let (tokens, _) = block_on(tokenize("a = 1\n", 0));
+    let mut input = TokenStream::from(tokens.as_slice());
+    block_on(input.next());
+    assert_eq!(input.position(), 1);
+
+    let marker = Pin::new(&mut input).mark().unwrap();
+    block_on(input.next());
+    block_on(input.next());
+    assert_eq!(input.position(), 3);
+    Pin::new(&mut input).rewind(marker).unwrap();
+    assert_eq!(input.position(), 1);
+    assert_eq!(input.index(), 1);
+
+    input.seek(4);
+    assert_eq!(input.position(), 4);
+    input.seek(0);
+    assert_eq!(input.position(), 0);
+}
+
+#[test]
+fn recovery() {
+    let source = "a = 1\nb = = 2\n= 3\nc = 4\nd 5\n[e]\nf = [1,\n[g]\nh = 6\n";
+    let (tokens, errors) = block_on(tokenize(source, 0));
+    assert_eq!(errors, Vec::new());
+    let (ast, errors) = block_on(parse(&tokens));
+    assert_eq!(keys(&ast), ["a", "c", "e", "g", "h"]);
+    // The unclosed array is reported at `h`, but parsing resumes from the table header.
+    assert_eq!(spans(&errors), [10..11, 14..15, 26..27, 44..45]);
+
+    // Newlines in brackets and after backslashes don't end broken statements.
+    let source = "a = [\n  1 =,\n]\nb = 1 = \\\n  = 2\nc = 3\n";
+    let (tokens, _) = block_on(tokenize(source, 0));
+    let (ast, errors) = block_on(parse(&tokens));
+    assert_eq!(keys(&ast), ["c"]);
+    assert_eq!(errors.len(), 2);
+}
+
+#[test]
+fn unknown() {
+    let (tokens, errors) = block_on(tokenize("a = 1 ~ 2\nb = $\nc = 3 ~\nd = 4\n", 0));
+    let unknown = tokens
+        .iter()
+        .filter(|token| token.kind == TokenKind::Unknown)
+        .map(|token| token.span.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(unknown, [6..7, 14..15, 22..23]);
+    assert_eq!(spans(&errors), [6..7, 14..15, 22..23]);
+    assert_eq!(tokens.last().map(|token| token.span.clone()), Some(29..30));
+
+    // Unknown tokens are reported only by the lexer.
+    let (ast, errors) = block_on(parse(&tokens));
+    assert_eq!(keys(&ast), ["d"]);
+    assert_eq!(errors, Vec::new());
+}
+
+#[test]
+fn eof_errors() {
+    for source in ["a = \"x", "a = 0x", "a = \"x\\", "a = 1\nb = \"あ"] {
+        let (tokens, errors) = block_on(tokenize(source, 10));
+        let end = 10 + source.len();
+        assert_eq!(spans(&errors), vec![end..end], "{source:?}");
+        assert_eq!(tokens.last().map(|token| token.span.end), Some(end));
+    }
+
+    // Newlines don't end strings, so unterminated strings are reported at the end of file.
+    let (_, errors) = block_on(tokenize("a = \"x\n", 10));
+    assert_eq!(spans(&errors), vec![17..17]);
+}
@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod tests;
+
+use alloc::string::String;
+use core::fmt;
+use core::ops::Range;
+use drake_types::ast::Literal;
+use drake_types::error::Error;
+use drake_types::ir::{Element, ElementKind, ExprKind, Ir};
+
+/// File types which modules can be rendered to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FileType {
+    /// JSON (`.json`)
+    Json,
+    /// YAML (`.yaml`, `.yml`)
+    Yaml,
+    /// TOML (`.toml`)
+    Toml,
+}
+
+impl FileType {
+    /// Gets a file type from its name like `json`.
+    pub fn from_name<S: AsRef<str>>(name: S) -> Option<Self> {
+        let name = name.as_ref();
+        if name.eq_ignore_ascii_case("json") {
+            Some(Self::Json)
+        } else if name.eq_ignore_ascii_case("yaml") || name.eq_ignore_ascii_case("yml") {
+            Some(Self::Yaml)
+        } else if name.eq_ignore_ascii_case("toml") {
+            Some(Self::Toml)
+        } else {
+            None
+        }
+    }
+
+    /// Infers a file type from the extension of the path.
+    pub fn from_path<S: AsRef<str>>(path: S) -> Option<Self> {
+        let path = path.as_ref();
+        let file = path.rsplit(['/', '\\']).next().unwrap_or(path);
+        let (stem, ext) = file.rsplit_once('.')?;
+        if stem.is_empty() {
+            None
+        } else {
+            Self::from_name(ext)
+        }
+    }
+
+    /// Renders an IR to this file type.
+    pub fn render<L: Clone>(self, ir: &Ir<L>) -> Result<String, Error<L>> {
+        match self {
+            Self::Json => drake_render::json::render(ir),
+            Self::Yaml => drake_render::yaml::render(ir),
+            Self::Toml => drake_render::toml::render(ir),
+        }
+    }
+}
+
+impl fmt::Display for FileType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json => write!(f, "json"),
+            Self::Yaml => write!(f, "yaml"),
+            Self::Toml => write!(f, "toml"),
+        }
+    }
+}
+
+/// A rendered module
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Output {
+    /// The destination specified by `@output`
+    pub path: Option<String>,
+    /// The file type rendered to
+    pub filetype: FileType,
+    /// The rendered text
+    pub content: String,
+}
+
+#[cfg(feature = "std")]
+impl Output {
+    /// Writes the content to the destination, resolved relative to `dir`.
+    ///
+    /// Returns `Ok(false)` without writing if `@output` is not specified.
+    pub fn write<P: AsRef<std::path::Path>>(&self, dir: P) -> std::io::Result<bool> {
+        match self.path {
+            Some(ref path) => {
+                std::fs::write(dir.as_ref().join(path), &self.content)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+/// Renders an IR to the given file type, or the one specified by built-ins.
+pub fn render(ir: &Ir<usize>, filetype: Option<FileType>) -> Result<Output, Error<usize>> {
+    let path = ir.builtin.output.as_ref().map(string).transpose()?;
+    let filetype = match filetype {
+        Some(filetype) => filetype,
+        None => match ir.builtin.filetype {
+            Some(ref elem) => {
+                let (name, span) = string(elem)?;
+                FileType::from_name(name).ok_or(Error::UnknownFileType { span })?
+            }
+            None => match path {
+                Some((ref path, ref span)) => FileType::from_path(path)
+                    .ok_or_else(|| Error::UnknownFileType { span: span.clone() })?,
+                None => return Err(Error::FileTypeNotSpecified),
+            },
+        },
+    };
+
+    Ok(Output {
+        path: path.map(|(path, _)| path),
+        filetype,
+        content: filetype.render(ir)?,
+    })
+}
+
+/// Extracts a string from a built-in value.
+fn string<L: Clone>(elem: &Element<L>) -> Result<(String, Range<L>), Error<L>> {
+    match elem.kind {
+        ElementKind::Expr(ref expr) => match expr.kind {
+            ExprKind::Literal(Literal::String(ref s)) => Ok((s.clone(), expr.span.clone())),
+            _ => Err(Error::TypeMismatch {
+                expected: "string",
+                span: expr.span.clone(),
+            }),
+        },
+        _ => Err(Error::TypeMismatch {
+            expected: "string",
+            span: elem.defined.clone(),
+        }),
+    }
+}
@@ -0,0 +1,88 @@
+use alloc::string::String;
+use drake_types::error::Error;
+use futures_executor::block_on;
+
+use super::{FileType, Output};
+use crate::module::Module;
+
+fn render(source: &str, filetype: Option<FileType>) -> (Option<Output>, Module) {
+    let mut module = Module::new(String::from("test.drake"), String::from(source));
+    (block_on(module.render(filetype)), module)
+}
+
+#[test]
+fn from_name() {
+    assert_eq!(FileType::from_name("json"), Some(FileType::Json));
+    assert_eq!(FileType::from_name("JSON"), Some(FileType::Json));
+    assert_eq!(FileType::from_name("yaml"), Some(FileType::Yaml));
+    assert_eq!(FileType::from_name("yml"), Some(FileType::Yaml));
+    assert_eq!(FileType::from_name("Toml"), Some(FileType::Toml));
+    assert_eq!(FileType::from_name("drake"), None);
+    assert_eq!(FileType::from_name(""), None);
+}
+
+#[test]
+fn from_path() {
+    assert_eq!(FileType::from_path("out.json"), Some(FileType::Json));
+    assert_eq!(FileType::from_path("dir/out.YML"), Some(FileType::Yaml));
+    assert_eq!(FileType::from_path("dir\\out.toml"), Some(FileType::Toml));
+    assert_eq!(FileType::from_path("a.b/out.yaml"), Some(FileType::Yaml));
+    assert_eq!(FileType::from_path("out.txt"), None);
+    assert_eq!(FileType::from_path("out"), None);
+    assert_eq!(FileType::from_path("a.json/out"), None);
+    assert_eq!(FileType::from_path(".json"), None);
+}
+
+#[test]
+fn infer() {
+    let (output, _) = render("@output = \"out.yml\"\na = 1\n", None);
+    assert_eq!(
+        output,
+        Some(Output {
+            path: Some(String::from("out.yml")),
+            filetype: FileType::Yaml,
+            content: String::from("a: 1\n"),
+        })
+    );
+
+    let (output, _) = render("@output = \"out.yml\"\n@filetype = \"json\"\n", None);
+    assert_eq!(
+        output.map(|output| (output.path, output.filetype)),
+        Some((Some(String::from("out.yml")), FileType::Json))
+    );
+
+    let (output, _) = render("@filetype = \"toml\"\na = 1\n", Some(FileType::Json));
+    assert_eq!(
+        output.map(|output| (output.path, output.filetype)),
+        Some((None, FileType::Json))
+    );
+
+    let (output, module) = render("@output = \"out.txt\"\n", None);
+    assert_eq!(output, None);
+    assert_eq!(
+        module.get_errors(),
+        &[Error::UnknownFileType { span: 10..19 }]
+    );
+
+    let (output, module) = render("@filetype = \"xml\"\n", None);
+    assert_eq!(output, None);
+    assert_eq!(
+        module.get_errors(),
+        &[Error::UnknownFileType { span: 12..17 }]
+    );
+
+    let (output, module) = render("a = 1\n", None);
+    assert_eq!(output, None);
+    assert_eq!(module.get_errors(), &[Error::FileTypeNotSpecified]);
+}
+
+#[test]
+fn errors() {
+    let (output, module) = render("@output = \"out.json\"\na = 1\na = 2\n", None);
+    assert_eq!(output, None);
+    assert_eq!(module.get_errors().len(), 1);
+
+    let (output, module) = render("a = [1,\n", Some(FileType::Json));
+    assert_eq!(output, None);
+    assert!(!module.get_errors().is_empty());
+}
@@ -0,0 +1,143 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use codespan_reporting::diagnostic::Label;
+use drake_types::error::Error;
+use futures_executor::block_on;
+
+use crate::loader::{Loader, MemoryLoader};
+use crate::module::FileType;
+use crate::Runtime;
+
+fn runtime(name: &str, files: &[(&str, &str)]) -> Runtime {
+    let loader: MemoryLoader = files
+        .iter()
+        .map(|(name, source)| (String::from(*name), String::from(*source)))
+        .collect();
+    let source = loader.load(&loader.resolve("", name)).unwrap();
+    Runtime::with_loader(String::from(name), source, loader)
+}
+
+#[test]
+fn render() {
+    let mut rt = runtime(
+        "main.drake",
+        &[
+            ("main.drake", "a = import(\"a.drake\")\n"),
+            ("a.drake", "b = 1\n"),
+        ],
+    );
+    let output = block_on(rt.render(0, Some(FileType::Json)));
+    assert_eq!(
+        output.map(|output| output.content),
+        Some(String::from("{\n  \"a\": {\n    \"b\": 1\n  }\n}\n"))
+    );
+    assert!(rt.diagnostics().is_empty());
+
+    // Errors in imported files prevent rendering.
+    let mut rt = runtime(
+        "main.drake",
+        &[
+            ("main.drake", "a = import(\"a.drake\")\nc = 2\n"),
+            ("a.drake", "b = 1 / 0\n"),
+        ],
+    );
+    assert_eq!(block_on(rt.render(0, Some(FileType::Json))), None);
+    assert_eq!(rt.diagnostics().len(), 1);
+
+    let mut rt = runtime(
+        "main.drake",
+        &[("main.drake", "a = import(\"missing.drake\")\n")],
+    );
+    assert_eq!(block_on(rt.render(0, Some(FileType::Json))), None);
+    assert_eq!(rt.diagnostics().len(), 1);
+}
+
+/// Gets the error codes and labels of diagnostics.
+fn diagnostics(rt: &Runtime) -> Vec<(Option<String>, Vec<Label<usize>>)> {
+    rt.diagnostics()
+        .into_iter()
+        .map(|diag| (diag.code, diag.labels))
+        .collect()
+}
+
+#[test]
+fn import_cycle() {
+    let mut rt = runtime(
+        "main.drake",
+        &[
+            ("main.drake", "a = import(\"a.drake\")\n"),
+            ("a.drake", "b = import(\"./main.drake\")\n"),
+        ],
+    );
+    block_on(rt.evaluate(0));
+    assert_eq!(rt.get_modules().len(), 2);
+    assert_eq!(
+        rt.get_module(1).unwrap().get_errors(),
+        &[Error::ImportCycle {
+            chain: vec![
+                String::from("main.drake"),
+                String::from("a.drake"),
+                String::from("main.drake"),
+            ],
+            span: 27..49,
+        }]
+    );
+    assert_eq!(
+        diagnostics(&rt),
+        [(
+            Some(String::from("E0026")),
+            vec![Label::primary(1, 4..26).with_message("imported here")]
+        )]
+    );
+
+    // The name of the main module is resolved like imported paths.
+    let mut rt = runtime(
+        "./dir/../self.drake",
+        &[("self.drake", "a = import(\"self.drake\")\n")],
+    );
+    block_on(rt.evaluate(0));
+    assert_eq!(rt.get_modules().len(), 1);
+    assert_eq!(
+        rt.get_module(0).unwrap().get_errors(),
+        &[Error::ImportCycle {
+            chain: vec![String::from("self.drake"), String::from("self.drake")],
+            span: 4..24,
+        }]
+    );
+}
+
+#[test]
+fn module_not_found() {
+    let mut rt = runtime(
+        "dir/main.drake",
+        &[
+            ("dir/main.drake", "a = import(\"a.drake\")\n"),
+            ("dir/a.drake", "b = 1\nc = import(\"../missing.drake\")\n"),
+            // Paths are resolved relative to the importer.
+            ("missing.drake", ""),
+            ("dir/missing.drake", ""),
+        ],
+    );
+    block_on(rt.evaluate(0));
+    assert!(rt.diagnostics().is_empty());
+    assert_eq!(rt.get_module(2).unwrap().get_name(), "missing.drake");
+
+    let mut rt = runtime(
+        "dir/main.drake",
+        &[
+            ("dir/main.drake", "a = import(\"a.drake\")\n"),
+            ("dir/a.drake", "b = 1\nc = import(\"missing.drake\")\n"),
+            ("missing.drake", ""),
+        ],
+    );
+    block_on(rt.evaluate(0));
+    assert_eq!(rt.get_modules().len(), 2);
+    assert_eq!(
+        diagnostics(&rt),
+        [(
+            Some(String::from("E0027")),
+            vec![Label::primary(1, 10..33).with_message("no such file")]
+        )]
+    );
+}
@@ -1,58 +1,93 @@
 #![no_std]
 extern crate alloc;
 
+mod operator;
+#[cfg(test)]
+mod tests;
+
+use alloc::boxed::Box;
+use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
+use core::ops::Range;
 use drake_types::ast::{
-    Expression, ExpressionKind, Key, KeyKind, Pattern, PatternKind, Statement, StatementKind,
-    TableHeaderKind,
+    BinaryOpKind, Expression, ExpressionKind, Key, KeyKind, KeyPattern, Literal, Pattern,
+    PatternKind, Statement, StatementKind, TableHeaderKind, UnaryOpKind,
 };
 use drake_types::error::Error;
-use drake_types::ir::{Builtin, Element, ElementKind, Expr, ExprKind, Ir, Table};
+use drake_types::ir::{
+    Builtin, BuiltinFunction, Element, ElementKind, Expr, ExprKind, Function, FunctionBody, Ir,
+    Table,
+};
 
-#[derive(Clone, Debug, PartialEq)]
-struct Environment<L> {
+/// The maximum depth of nested function calls
+///
+/// Each call also uses the stack for nested expressions in the body, so the limit is kept low
+/// enough not to overflow small stacks like 1 MiB of the main thread on Windows.
+const RECURSION_LIMIT: usize = 32;
+
+/// A handler of imports of other files
+pub trait Importer<L> {
+    /// Gets the value of the file imported by the path at the span.
+    fn import(&self, path: &str, span: Range<L>) -> Result<Expr<L>, Error<L>>;
+}
+
+/// Imports are not supported.
+impl<L> Importer<L> for () {
+    fn import(&self, _: &str, span: Range<L>) -> Result<Expr<L>, Error<L>> {
+        Err(Error::NotSupported {
+            feature: "imports",
+            span,
+        })
+    }
+}
+
+struct Environment<'a, L> {
+    importer: &'a dyn Importer<L>,
     root: Table<Element<L>>,
     builtin: Builtin<L>,
     current: Option<Current<L>>,
+    implicit: Vec<Vec<Key<L>>>,
+    definition: Option<Definition<L>>,
+    frames: Vec<Frame<L>>,
     errors: Vec<Error<L>>,
 }
 
-impl<L> Default for Environment<L> {
+impl<'a, L: Clone> Environment<'a, L> {
     #[inline]
-    fn default() -> Self {
+    fn new(importer: &'a dyn Importer<L>) -> Self {
         Self {
+            importer,
             root: Table::new(),
             builtin: Builtin::new(),
             current: None,
+            implicit: Vec::new(),
+            definition: None,
+            frames: Vec::new(),
             errors: Vec::new(),
         }
     }
-}
-
-impl<L: Clone> Environment<L> {
-    #[inline]
-    fn new() -> Self {
-        Self::default()
-    }
 
     fn bind(&mut self, pattern: Pattern<L>, elem: ElementKind<L>) {
         match pattern.kind {
-            PatternKind::Key(key) => {
-                let table = match self.current {
-                    Some(ref mut cur) => match cur.elem.as_mut_table() {
-                        Some(table) => table,
-                        None => {
-                            self.errors.push(Error::Unexpected);
-                            return;
-                        }
-                    },
-                    None => &mut self.root,
-                };
-
-                insert_elem(table, key, elem, &mut self.errors);
-            }
+            PatternKind::Key(key) => self.bind_keys(vec![key], elem, false),
+            PatternKind::Dotted(keys) => self.bind_keys(keys, elem, false),
+            PatternKind::Root(keys) => self.bind_keys(keys, elem, true),
             PatternKind::Builtin(key) => self.builtin_write(key, elem),
+            PatternKind::Array(pats, rest) => match elem {
+                ElementKind::Expr(expr) => self.bind_array(pats, rest, expr, pattern.span),
+                _ => self.errors.push(Error::NotSupported {
+                    feature: "destructions in table headers",
+                    span: pattern.span,
+                }),
+            },
+            PatternKind::Table(keys) => match elem {
+                ElementKind::Expr(expr) => self.bind_table(keys, expr, pattern.span),
+                _ => self.errors.push(Error::NotSupported {
+                    feature: "destructions in table headers",
+                    span: pattern.span,
+                }),
+            },
             _ => {
                 self.errors.push(Error::NotSupported {
                     feature: "unknown pattern",
@@ -62,21 +97,266 @@ impl<L: Clone> Environment<L> {
         };
     }
 
-    fn header(&mut self, kind: TableHeaderKind, pattern: Pattern<L>, default: Option<Expr<L>>) {
+    /// Binds a value to the dotted keys in the current scope or the root scope, creating
+    /// intermediate tables.
+    ///
+    /// Intermediate tables created by table headers can be extended by later headers.
+    fn bind_keys(&mut self, mut keys: Vec<Key<L>>, elem: ElementKind<L>, root: bool) {
+        let header = !matches!(elem, ElementKind::Expr(_));
+        let Some(last) = keys.pop() else {
+            self.errors.push(Error::Unexpected);
+            return;
+        };
+
+        let mut table = match (self.frames.last_mut(), &mut self.current) {
+            // Values can't be bound out of function scopes.
+            (Some(frame), _) => match frame.table {
+                Some(ref mut table) if !root => table,
+                _ => {
+                    self.errors.push(Error::NotSupported {
+                        feature: "root key patterns in functions",
+                        span: last.span,
+                    });
+                    return;
+                }
+            },
+            (None, Some(cur)) if !root => match cur.elem.as_mut_table() {
+                Some(table) => table,
+                None => {
+                    self.errors.push(Error::Unexpected);
+                    return;
+                }
+            },
+            _ => &mut self.root,
+        };
+
+        for (i, key) in keys.iter().enumerate() {
+            let created = get_elem(table, key).is_none();
+            table = match sub_table(table, key.clone(), &mut self.errors) {
+                Some(table) => table,
+                None => return,
+            };
+            if created && header {
+                self.implicit.push(keys[..=i].to_vec());
+            }
+        }
+
+        keys.push(last.clone());
+        let implicit = self.implicit.iter().position(|path| *path == keys);
+        match (implicit, get_mut_elem(table, &last), elem) {
+            (
+                Some(i),
+                Some(Element {
+                    kind: ElementKind::Table(ref mut existing, ref mut existing_default),
+                    ref mut defined,
+                }),
+                ElementKind::Table(table, default),
+            ) => {
+                self.implicit.remove(i);
+                extend_table(existing, table, &mut self.errors);
+                *existing_default = default;
+                *defined = last.span;
+            }
+            (_, _, elem) => insert_elem(table, last, elem, &mut self.errors),
+        }
+    }
+
+    /// Binds elements of an array to the patterns.
+    fn bind_array(
+        &mut self,
+        pats: Vec<Pattern<L>>,
+        rest: Option<Box<Pattern<L>>>,
+        expr: Expr<L>,
+        pattern: Range<L>,
+    ) {
+        let mut arr = match expr.kind {
+            ExprKind::Array(arr) => arr,
+            _ => {
+                self.errors.push(Error::PatternMismatch {
+                    expected: "array",
+                    pattern,
+                    span: expr.span,
+                });
+                return;
+            }
+        };
+
+        if arr.len() < pats.len() || (rest.is_none() && arr.len() != pats.len()) {
+            self.errors.push(Error::LengthMismatch {
+                expected: pats.len(),
+                rest: rest.is_some(),
+                found: arr.len(),
+                pattern,
+                span: expr.span,
+            });
+            return;
+        }
+
+        let rest_arr = arr.split_off(pats.len());
+        for (pat, elem) in pats.into_iter().zip(arr) {
+            self.bind(pat, ElementKind::Expr(elem));
+        }
+        if let Some(rest) = rest {
+            let elem = Expr {
+                kind: ExprKind::Array(rest_arr),
+                span: expr.span,
+            };
+            self.bind(*rest, ElementKind::Expr(elem));
+        }
+    }
+
+    /// Binds values in a table to the patterns.
+    ///
+    /// Default values are evaluated only if the key is not found.
+    fn bind_table(&mut self, keys: Vec<KeyPattern<L>>, expr: Expr<L>, pattern: Range<L>) {
+        let mut table = match expr.kind {
+            ExprKind::Table(table) => table,
+            _ => {
+                self.errors.push(Error::PatternMismatch {
+                    expected: "table",
+                    pattern,
+                    span: expr.span,
+                });
+                return;
+            }
+        };
+
+        for KeyPattern {
+            key,
+            pattern,
+            default,
+        } in keys
+        {
+            // Local keys of other tables are not accessible.
+            let value = match key.kind {
+                KeyKind::Normal => table.global.remove(&key.name),
+                _ => None,
+            };
+
+            let value = match (value, default) {
+                (Some(value), _) => value,
+                (None, Some(default)) => self.expression(default),
+                (None, None) => {
+                    self.errors.push(match get_expr(&table, &key) {
+                        Some(value) => Error::InaccessibleKey {
+                            found: key.span,
+                            defined: value.span.clone(),
+                        },
+                        None => Error::MissingKey {
+                            found: key.span,
+                            span: expr.span.clone(),
+                        },
+                    });
+                    continue;
+                }
+            };
+
+            let pattern = pattern.unwrap_or_else(|| Pattern {
+                span: key.span.clone(),
+                kind: PatternKind::Key(key),
+            });
+            self.bind(pattern, ElementKind::Expr(value));
+        }
+    }
+
+    fn header(
+        &mut self,
+        kind: TableHeaderKind,
+        pattern: Pattern<L>,
+        default: Option<Expression<L>>,
+    ) {
         if let Some(mut cur) = core::mem::take(&mut self.current) {
             if cur.is_movable(kind, &pattern) {
+                // Initial tables of the first element are used for every element.
+                if let Some(default) = default {
+                    self.expression(default);
+                }
                 cur.next_array(&mut self.errors);
                 self.current = Some(cur);
-            } else {
-                self.bind(cur.pattern, into_element(cur.elem, cur.default));
-                self.current = Some(Current::new(kind, pattern, default));
+                return;
             }
-        } else {
-            self.current = Some(Current::new(kind, pattern, default));
+            self.bind(cur.pattern, into_element(cur.elem, cur.default));
         }
+
+        let default = default.map(|default| self.expression(default));
+        self.current = Some(Current::new(kind, pattern, default));
+    }
+
+    /// Starts a table function, which takes following value bindings as the body.
+    fn table_function(&mut self, key: Key<L>, parameters: Vec<Key<L>>) {
+        self.end_definition();
+        if let Some(cur) = core::mem::take(&mut self.current) {
+            self.bind(cur.pattern, into_element(cur.elem, cur.default));
+        }
+
+        self.definition = Some(Definition {
+            key,
+            parameters,
+            body: Vec::new(),
+        });
+    }
+
+    /// Binds the table function being defined, if exists.
+    fn end_definition(&mut self) {
+        if let Some(def) = core::mem::take(&mut self.definition) {
+            self.function(def.key, def.parameters, FunctionBody::Table(def.body));
+        }
+    }
+
+    /// Binds a function to the key in the current scope.
+    fn function(&mut self, key: Key<L>, parameters: Vec<Key<L>>, body: FunctionBody<L>) {
+        let Some(func) = self.make_function(parameters, body, key.span.clone()) else {
+            return;
+        };
+        let expr = Expr {
+            kind: ExprKind::Function(Box::new(func)),
+            span: key.span.clone(),
+        };
+        self.bind(
+            Pattern {
+                span: key.span.clone(),
+                kind: PatternKind::Key(key),
+            },
+            ElementKind::Expr(expr),
+        );
+    }
+
+    /// Makes a function closing over the current scope, or returns `None` if parameters are
+    /// duplicated.
+    fn make_function(
+        &mut self,
+        parameters: Vec<Key<L>>,
+        body: FunctionBody<L>,
+        defined: Range<L>,
+    ) -> Option<Function<L>> {
+        for (i, param) in parameters.iter().enumerate() {
+            if let Some(existing) = parameters[..i].iter().find(|existing| *existing == param) {
+                self.errors.push(Error::DuplicateKey {
+                    found: param.span.clone(),
+                    existing: Some(existing.span.clone()),
+                });
+                return None;
+            }
+        }
+
+        Some(Function {
+            parameters,
+            body,
+            scope: self.scope().map(<[_]>::to_vec).unwrap_or_default(),
+            captured: self.captured(),
+            defined,
+        })
     }
 
     fn builtin_write(&mut self, key: Key<L>, elem: ElementKind<L>) {
+        if !self.frames.is_empty() {
+            self.errors.push(Error::NotSupported {
+                feature: "built-in keys in functions",
+                span: key.span,
+            });
+            return;
+        }
+
         if key.kind != KeyKind::Normal {
             self.errors.push(Error::BuiltinNotFound { span: key.span });
             return;
@@ -103,17 +383,566 @@ impl<L: Clone> Environment<L> {
                         existing: Some(filetype.defined.clone()),
                     });
                 } else {
-                    self.builtin.output = Some(Element {
+                    self.builtin.filetype = Some(Element {
                         kind: elem,
                         defined: key.span,
                     });
                 }
             }
+            "true" | "false" | "null" | "inf" | "nan" | "map" | "filter" => {
+                self.errors.push(Error::BuiltinReadOnly { span: key.span })
+            }
             _ => self.errors.push(Error::BuiltinNotFound { span: key.span }),
         }
     }
 
+    /// Evaluates an expression, or an empty table if errors occurred.
+    ///
+    /// Expressions are evaluated recursively, so each kind is evaluated in its own method to keep
+    /// the stack used by nested expressions and function calls small.
+    fn expression(&mut self, expr: Expression<L>) -> Expr<L> {
+        let span = expr.span;
+        let kind = match expr.kind {
+            ExpressionKind::Literal(lit) => Some(ExprKind::Literal(lit)),
+            ExpressionKind::Array(arr) => Some(self.array(arr)),
+            ExpressionKind::InlineTable(pairs) => Some(self.inline_table(pairs)),
+            ExpressionKind::Key(key) => {
+                let res = self.reference(&key, false);
+                self.report(res)
+            }
+            ExpressionKind::Root(key) => {
+                let res = self.reference(&key, true);
+                self.report(res)
+            }
+            ExpressionKind::Builtin(key) => self.report(builtin_read(key)),
+            ExpressionKind::BinaryOp(kind, lhs, rhs) => self.binary(kind, *lhs, *rhs, span.clone()),
+            ExpressionKind::UnaryOp(kind, operand) => self.unary(kind, *operand, span.clone()),
+            ExpressionKind::Field(expr, key) => self.field(*expr, key),
+            ExpressionKind::Index(expr, index) => self.index(*expr, *index),
+            ExpressionKind::Import(path) => {
+                let res = self.importer.import(&path, span.clone());
+                self.report(res.map(|value| value.kind))
+            }
+            ExpressionKind::InlineFunction(params, body) => {
+                let body = FunctionBody::Expression(*body);
+                self.make_function(params, body, span.clone())
+                    .map(|func| ExprKind::Function(Box::new(func)))
+            }
+            ExpressionKind::Call(func, args, named) => self.call(*func, args, named, span.clone()),
+            _ => {
+                self.errors.push(Error::NotSupported {
+                    feature: "unknown expressions",
+                    span: span.clone(),
+                });
+                None
+            }
+        };
+
+        Expr {
+            kind: kind.unwrap_or_else(|| ExprKind::Table(Table::new())),
+            span,
+        }
+    }
+
+    /// Reports the error if exists.
+    fn report(&mut self, res: Result<ExprKind<L>, Error<L>>) -> Option<ExprKind<L>> {
+        match res {
+            Ok(kind) => Some(kind),
+            Err(err) => {
+                self.errors.push(err);
+                None
+            }
+        }
+    }
+
+    fn array(&mut self, arr: Vec<Expression<L>>) -> ExprKind<L> {
+        let mut res = Vec::with_capacity(arr.len());
+        for elem in arr {
+            res.push(self.expression(elem));
+        }
+        ExprKind::Array(res)
+    }
+
+    fn inline_table(&mut self, pairs: Vec<(Key<L>, Expression<L>)>) -> ExprKind<L> {
+        let mut table = Table::new();
+        for (key, expr) in pairs {
+            let expr = self.expression(expr);
+            insert_expr(&mut table, key, expr, &mut self.errors);
+        }
+        ExprKind::Table(table)
+    }
+
+    /// Evaluates an unary operation, or returns `None` if errors occurred.
+    fn unary(
+        &mut self,
+        kind: UnaryOpKind,
+        operand: Expression<L>,
+        span: Range<L>,
+    ) -> Option<ExprKind<L>> {
+        // Errors in operands are not reported again.
+        let errors = self.errors.len();
+        let operand = self.expression(operand);
+        if self.errors.len() != errors {
+            return None;
+        }
+        self.report(operator::unary(kind, operand, span))
+    }
+
+    /// Evaluates a binary operation, or returns `None` if errors occurred.
+    ///
+    /// Logical operators don't evaluate the right-hand side if the result is determined.
+    fn binary(
+        &mut self,
+        kind: BinaryOpKind,
+        lhs: Expression<L>,
+        rhs: Expression<L>,
+        span: Range<L>,
+    ) -> Option<ExprKind<L>> {
+        // Errors in operands are not reported again.
+        let errors = self.errors.len();
+        let lhs = self.expression(lhs);
+        if self.errors.len() != errors {
+            return None;
+        }
+
+        let res = match kind {
+            BinaryOpKind::And | BinaryOpKind::Or => match lhs.kind {
+                ExprKind::Literal(Literal::Boolean(b)) if b == (kind == BinaryOpKind::Or) => {
+                    Ok(operator::boolean(b))
+                }
+                ExprKind::Literal(Literal::Boolean(_)) => {
+                    let rhs = self.expression(rhs);
+                    match rhs.kind {
+                        _ if self.errors.len() != errors => return None,
+                        ExprKind::Literal(Literal::Boolean(b)) => Ok(operator::boolean(b)),
+                        _ => Err(operator::mismatch("boolean", rhs.span)),
+                    }
+                }
+                _ => Err(operator::mismatch("boolean", lhs.span)),
+            },
+            _ => {
+                let rhs = self.expression(rhs);
+                if self.errors.len() != errors {
+                    return None;
+                }
+                operator::binary(kind, lhs, rhs, span)
+            }
+        };
+
+        self.report(res)
+    }
+
+    /// Evaluates an access to a value in a table, or returns `None` if errors occurred.
+    ///
+    /// Local keys can be accessed only in tables enclosing the current scope.
+    fn field(&mut self, expr: Expression<L>, key: Key<L>) -> Option<ExprKind<L>> {
+        let accessible = key.kind != KeyKind::Local || self.encloses(&expr);
+
+        // Errors in the table are not reported again.
+        let errors = self.errors.len();
+        let expr = self.expression(expr);
+        if self.errors.len() != errors {
+            return None;
+        }
+
+        let res = match expr.kind {
+            ExprKind::Table(ref table) => match get_expr(table, &key) {
+                Some(value) if accessible => Ok(value.kind.clone()),
+                Some(value) => Err(Error::InaccessibleKey {
+                    found: key.span,
+                    defined: value.span.clone(),
+                }),
+                None => Err(Error::FieldNotFound { span: key.span }),
+            },
+            _ => Err(operator::mismatch("table", expr.span)),
+        };
+
+        self.report(res)
+    }
+
+    /// Evaluates an access to an element of an array or a table, or returns `None` if errors
+    /// occurred.
+    fn index(&mut self, expr: Expression<L>, index: Expression<L>) -> Option<ExprKind<L>> {
+        // Errors in operands are not reported again.
+        let errors = self.errors.len();
+        let expr = self.expression(expr);
+        let index = self.expression(index);
+        if self.errors.len() != errors {
+            return None;
+        }
+
+        let res = match (expr.kind, index.kind) {
+            (ExprKind::Array(mut arr), ExprKind::Literal(Literal::Integer(i))) => {
+                match usize::try_from(i).ok().filter(|i| *i < arr.len()) {
+                    Some(i) => Ok(arr.swap_remove(i).kind),
+                    None => Err(Error::IndexOutOfRange {
+                        len: arr.len(),
+                        span: index.span,
+                    }),
+                }
+            }
+            (ExprKind::Array(arr), ExprKind::Literal(Literal::NegativeInteger(_))) => {
+                Err(Error::IndexOutOfRange {
+                    len: arr.len(),
+                    span: index.span,
+                })
+            }
+            (ExprKind::Array(_), _) => Err(operator::mismatch("integer", index.span)),
+            // Strings are always treated as normal keys.
+            (ExprKind::Table(mut table), ExprKind::Literal(Literal::String(key))) => {
+                match table.global.remove(&key) {
+                    Some(value) => Ok(value.kind),
+                    None => Err(Error::FieldNotFound { span: index.span }),
+                }
+            }
+            (ExprKind::Table(_), _) => Err(operator::mismatch("string", index.span)),
+            _ => Err(operator::mismatch("array or table", expr.span)),
+        };
+
+        self.report(res)
+    }
+
+    /// Evaluates a function call in a new function scope, or returns `None` if errors occurred.
+    ///
+    /// Arguments are evaluated in the caller's scope before the call.
+    fn call(
+        &mut self,
+        func: Expression<L>,
+        args: Vec<Expression<L>>,
+        named: Vec<(Key<L>, Expression<L>)>,
+        span: Range<L>,
+    ) -> Option<ExprKind<L>> {
+        // Errors in operands are not reported again.
+        let errors = self.errors.len();
+        let func = self.expression(func);
+        let args: Vec<_> = args.into_iter().map(|arg| self.expression(arg)).collect();
+        let named: Vec<_> = named
+            .into_iter()
+            .map(|(key, arg)| (key, self.expression(arg)))
+            .collect();
+        if self.errors.len() != errors {
+            return None;
+        }
+
+        match func.kind {
+            ExprKind::Function(func) => self.apply(*func, args, named, span),
+            _ => {
+                self.errors.push(operator::mismatch("function", func.span));
+                None
+            }
+        }
+    }
+
+    /// Calls a function with evaluated arguments, or returns `None` if errors occurred.
+    fn apply(
+        &mut self,
+        func: Function<L>,
+        args: Vec<Expr<L>>,
+        named: Vec<(Key<L>, Expr<L>)>,
+        span: Range<L>,
+    ) -> Option<ExprKind<L>> {
+        if self.frames.len() >= RECURSION_LIMIT {
+            self.errors.push(Error::RecursionLimit {
+                limit: RECURSION_LIMIT,
+                span,
+            });
+            return None;
+        }
+
+        let Function {
+            parameters,
+            body,
+            scope,
+            captured,
+            defined,
+        } = func;
+        let arguments = self.arguments(parameters, captured, defined, args, named, &span)?;
+
+        let errors = self.errors.len();
+        let res = match body {
+            FunctionBody::Expression(body) => {
+                self.frames.push(Frame {
+                    arguments,
+                    table: None,
+                    scope,
+                });
+                let res = self.expression(body);
+                self.frames.pop();
+                res.kind
+            }
+            FunctionBody::Table(body) => {
+                self.frames.push(Frame {
+                    arguments,
+                    table: Some(Table::new()),
+                    scope,
+                });
+                for (pattern, expr) in body {
+                    let expr = self.expression(expr);
+                    self.bind(pattern, ElementKind::Expr(expr));
+                }
+                let frame = self.frames.pop()?;
+                ExprKind::Table(table_expr(&frame.table?, None))
+            }
+            FunctionBody::Builtin(builtin) => self.builtin_call(builtin, arguments, span)?,
+        };
+
+        if self.errors.len() != errors {
+            None
+        } else {
+            Some(res)
+        }
+    }
+
+    /// Binds arguments to parameters over captured values, or returns `None` if errors occurred.
+    fn arguments(
+        &mut self,
+        parameters: Vec<Key<L>>,
+        mut table: Table<Expr<L>>,
+        defined: Range<L>,
+        args: Vec<Expr<L>>,
+        named: Vec<(Key<L>, Expr<L>)>,
+        span: &Range<L>,
+    ) -> Option<Table<Expr<L>>> {
+        if args.len() > parameters.len() {
+            self.errors.push(Error::ArityMismatch {
+                expected: parameters.len(),
+                found: args.len() + named.len(),
+                span: span.clone(),
+                defined,
+            });
+            return None;
+        }
+
+        // Spans of arguments are kept to report duplicates.
+        let errors = self.errors.len();
+        let mut arguments: Vec<Option<Expr<L>>> = vec![None; parameters.len()];
+        for (slot, arg) in arguments.iter_mut().zip(args) {
+            *slot = Some(arg);
+        }
+        for (key, arg) in named {
+            match parameters.iter().position(|param| *param == key) {
+                Some(i) => match arguments[i] {
+                    Some(ref existing) => self.errors.push(Error::DuplicateArgument {
+                        found: key.span,
+                        existing: existing.span.clone(),
+                    }),
+                    None => arguments[i] = Some(arg),
+                },
+                None => self.errors.push(Error::UnknownArgument {
+                    span: key.span,
+                    defined: defined.clone(),
+                }),
+            }
+        }
+
+        // Arguments shadow captured values.
+        for param in &parameters {
+            match param.kind {
+                KeyKind::Normal => table.global.remove(&param.name),
+                _ => table.local.remove(&param.name),
+            };
+        }
+        for (param, arg) in parameters.into_iter().zip(arguments) {
+            match arg {
+                Some(arg) => insert_expr(&mut table, param, arg, &mut self.errors),
+                None => self.errors.push(Error::MissingArgument {
+                    parameter: param.span,
+                    span: span.clone(),
+                }),
+            }
+        }
+
+        if self.errors.len() != errors {
+            None
+        } else {
+            Some(table)
+        }
+    }
+
+    /// Evaluates a call of a built-in function, or returns `None` if errors occurred.
+    fn builtin_call(
+        &mut self,
+        builtin: BuiltinFunction,
+        mut args: Table<Expr<L>>,
+        span: Range<L>,
+    ) -> Option<ExprKind<L>> {
+        let (Some(arr), Some(func)) = (args.global.remove("array"), args.global.remove("function"))
+        else {
+            self.errors.push(Error::Unexpected);
+            return None;
+        };
+
+        let arr = match arr.kind {
+            ExprKind::Array(arr) => arr,
+            _ => {
+                self.errors.push(operator::mismatch("array", arr.span));
+                return None;
+            }
+        };
+        let ExprKind::Function(ref function) = func.kind else {
+            self.errors.push(operator::mismatch("function", func.span));
+            return None;
+        };
+
+        let mut res = Vec::with_capacity(arr.len());
+        for elem in arr {
+            let kind = self.apply(
+                (**function).clone(),
+                vec![elem.clone()],
+                Vec::new(),
+                span.clone(),
+            )?;
+            match (builtin, kind) {
+                (BuiltinFunction::Map, kind) => res.push(Expr {
+                    kind,
+                    span: elem.span,
+                }),
+                (BuiltinFunction::Filter, ExprKind::Literal(Literal::Boolean(true))) => {
+                    res.push(elem)
+                }
+                (BuiltinFunction::Filter, ExprKind::Literal(Literal::Boolean(false))) => (),
+                (BuiltinFunction::Filter, _) => {
+                    self.errors.push(operator::mismatch("boolean", func.span));
+                    return None;
+                }
+            }
+        }
+        Some(ExprKind::Array(res))
+    }
+
+    /// Checks whether the expression refers to a table enclosing the current scope.
+    fn encloses(&self, expr: &Expression<L>) -> bool {
+        let mut path = Vec::new();
+        if !self.path(expr, &mut path) {
+            return false;
+        }
+
+        let Some(scope) = self.scope() else {
+            return false;
+        };
+        path.len() <= scope.len() && path.iter().zip(scope).all(|(key1, key2)| *key1 == key2)
+    }
+
+    /// Gets keys from the root scope to the value referred by the expression.
+    fn path<'b>(&'b self, expr: &'b Expression<L>, path: &mut Vec<&'b Key<L>>) -> bool {
+        match expr.kind {
+            ExpressionKind::Key(ref key) => {
+                if let Some(frame) = self.frames.last() {
+                    // Arguments and tables made by functions are not a part of other tables.
+                    if get_expr(&frame.arguments, key).is_some()
+                        || frame
+                            .table
+                            .as_ref()
+                            .is_some_and(|t| get_elem(t, key).is_some())
+                    {
+                        return false;
+                    }
+                }
+                if self.scope_get(key).is_some() {
+                    match self.scope() {
+                        Some(scope) => path.extend(scope),
+                        None => return false,
+                    }
+                }
+                path.push(key);
+                true
+            }
+            ExpressionKind::Root(ref key) => {
+                path.push(key);
+                true
+            }
+            ExpressionKind::Field(ref expr, ref key) => {
+                let res = self.path(expr, path);
+                path.push(key);
+                res
+            }
+            _ => false,
+        }
+    }
+
+    /// Resolves a key reference to a copy of the value.
+    ///
+    /// Normal keys are looked up in the current scope, and local keys also in outer scopes.
+    fn reference(&self, key: &Key<L>, root: bool) -> Result<ExprKind<L>, Error<L>> {
+        if !root {
+            if let Some(kind) = self.scope_get(key) {
+                return Ok(kind);
+            }
+        }
+
+        if root || key.kind == KeyKind::Local {
+            if let Some(elem) = get_elem(&self.root, key) {
+                return Ok(element_expr(elem));
+            }
+        }
+
+        let defined = match key.kind {
+            KeyKind::Local => find_local(&self.root, &key.name),
+            _ => None,
+        };
+        Err(match defined {
+            Some(defined) => Error::InaccessibleKey {
+                found: key.span.clone(),
+                defined,
+            },
+            None => Error::KeyNotFound {
+                span: key.span.clone(),
+            },
+        })
+    }
+
+    /// Gets keys from the root scope to the current scope.
+    ///
+    /// In function scopes, the scope where the function is defined is used.
+    fn scope(&self) -> Option<&[Key<L>]> {
+        match (self.frames.last(), &self.current) {
+            (Some(frame), _) => Some(&frame.scope),
+            (None, Some(cur)) => cur.scope(),
+            (None, None) => Some(&[]),
+        }
+    }
+
+    /// Gets a copy of the value in the current scope.
+    ///
+    /// In function scopes, keys except arguments are looked up in the scope where the function
+    /// is defined.
+    fn scope_get(&self, key: &Key<L>) -> Option<ExprKind<L>> {
+        let Some(frame) = self.frames.last() else {
+            return match self.current {
+                Some(ref cur) => cur.get(key),
+                None => get_elem(&self.root, key).map(element_expr),
+            };
+        };
+
+        if let Some(elem) = frame.table.as_ref().and_then(|table| get_elem(table, key)) {
+            return Some(element_expr(elem));
+        }
+        if let Some(expr) = get_expr(&frame.arguments, key) {
+            return Some(expr.kind.clone());
+        }
+        match self.current {
+            Some(ref cur) if cur.scope() == Some(&frame.scope) => cur.get(key),
+            _ => lookup(&self.root, &frame.scope, key),
+        }
+    }
+
+    /// Gets a copy of values in the current function scope, to be captured by functions.
+    fn captured(&self) -> Table<Expr<L>> {
+        let Some(frame) = self.frames.last() else {
+            return Table::new();
+        };
+
+        let mut captured = frame.arguments.clone();
+        if let Some(ref table) = frame.table {
+            let table = table_expr(table, None);
+            captured.global.extend(table.global);
+            captured.local.extend(table.local);
+        }
+        captured
+    }
+
     fn close(mut self) -> (Ir<L>, Vec<Error<L>>) {
+        self.end_definition();
         if let Some(cur) = core::mem::take(&mut self.current) {
             self.bind(cur.pattern, into_element(cur.elem, cur.default));
         }
@@ -155,6 +984,30 @@ impl<L: Clone> Current<L> {
         }
     }
 
+    /// Gets a copy of the value in the current table, or in the initial table.
+    fn get(&self, key: &Key<L>) -> Option<ExprKind<L>> {
+        if let Some(elem) = self.elem.as_table().and_then(|table| get_elem(table, key)) {
+            return Some(element_expr(elem));
+        }
+
+        match self.default {
+            Some(Expr {
+                kind: ExprKind::Table(ref table),
+                ..
+            }) => get_expr(table, key).map(|expr| expr.kind.clone()),
+            _ => None,
+        }
+    }
+
+    /// Gets keys from the root scope to the current scope.
+    fn scope(&self) -> Option<&[Key<L>]> {
+        match self.pattern.kind {
+            PatternKind::Key(ref key) => Some(core::slice::from_ref(key)),
+            PatternKind::Dotted(ref keys) | PatternKind::Root(ref keys) => Some(keys),
+            _ => None,
+        }
+    }
+
     #[inline]
     fn is_movable(&self, kind: TableHeaderKind, pattern: &Pattern<L>) -> bool {
         kind == TableHeaderKind::Array
@@ -170,6 +1023,23 @@ impl<L: Clone> Current<L> {
     }
 }
 
+/// A function scope
+#[derive(Clone, Debug, PartialEq)]
+struct Frame<L> {
+    arguments: Table<Expr<L>>,
+    /// The table made by a table function
+    table: Option<Table<Element<L>>>,
+    scope: Vec<Key<L>>,
+}
+
+/// A table function being defined
+#[derive(Clone, Debug, PartialEq)]
+struct Definition<L> {
+    key: Key<L>,
+    parameters: Vec<Key<L>>,
+    body: Vec<(Pattern<L>, Expression<L>)>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 enum CurrentElem<L> {
     Table(Table<Element<L>>),
@@ -177,6 +1047,13 @@ enum CurrentElem<L> {
 }
 
 impl<L> CurrentElem<L> {
+    fn as_table(&self) -> Option<&Table<Element<L>>> {
+        match self {
+            Self::Table(table) => Some(table),
+            Self::Array(arr) => arr.last(),
+        }
+    }
+
     fn as_mut_table(&mut self) -> Option<&mut Table<Element<L>>> {
         match self {
             Self::Table(table) => Some(table),
@@ -186,19 +1063,45 @@ impl<L> CurrentElem<L> {
 }
 
 /// Interprets an AST to IR.
+///
+/// Imports are reported as errors. Use [`interpret_with`] to import other files.
+#[inline]
 pub fn interpret<L: Clone>(ast: &[Statement<L>]) -> (Ir<L>, Vec<Error<L>>) {
-    let mut env = Environment::new();
+    interpret_with(ast, &())
+}
+
+/// Interprets an AST to IR, importing other files by the importer.
+pub fn interpret_with<L: Clone>(
+    ast: &[Statement<L>],
+    importer: &dyn Importer<L>,
+) -> (Ir<L>, Vec<Error<L>>) {
+    let mut env = Environment::new(importer);
     for stmt in ast {
         match stmt.kind {
-            StatementKind::ValueBinding(ref pattern, ref expr) => {
-                let expr = expression(expr.clone(), &mut env.errors);
-                env.bind(pattern.clone(), ElementKind::Expr(expr))
-            }
+            StatementKind::ValueBinding(ref pattern, ref expr) => match env.definition {
+                Some(ref mut def) => def.body.push((pattern.clone(), expr.clone())),
+                None => {
+                    let expr = env.expression(expr.clone());
+                    env.bind(pattern.clone(), ElementKind::Expr(expr))
+                }
+            },
             StatementKind::TableHeader(kind, ref pattern, ref default) => {
-                let default = default
-                    .as_ref()
-                    .map(|def| expression(def.clone(), &mut env.errors));
-                env.header(kind, pattern.clone(), default)
+                env.end_definition();
+                env.header(kind, pattern.clone(), default.clone())
+            }
+            StatementKind::ExpressionFunction(..) if env.definition.is_some() => {
+                env.errors.push(Error::NotSupported {
+                    feature: "function definitions in table functions",
+                    span: stmt.span.clone(),
+                })
+            }
+            StatementKind::ExpressionFunction(ref key, ref params, ref body) => env.function(
+                key.clone(),
+                params.clone(),
+                FunctionBody::Expression(body.clone()),
+            ),
+            StatementKind::TableFunction(ref key, ref params) => {
+                env.table_function(key.clone(), params.clone())
             }
             _ => env.errors.push(Error::NotSupported {
                 feature: "unknown statements",
@@ -210,34 +1113,137 @@ pub fn interpret<L: Clone>(ast: &[Statement<L>]) -> (Ir<L>, Vec<Error<L>>) {
     env.close()
 }
 
-fn expression<L: Clone>(expr: Expression<L>, errors: &mut Vec<Error<L>>) -> Expr<L> {
+/// Gets the root table without local keys, to be imported by other files.
+///
+/// Functions can't be imported, and are reported as errors.
+pub fn export<L: Clone>(ir: &Ir<L>) -> (Table<Expr<L>>, Vec<Error<L>>) {
+    let mut errors = Vec::new();
+    let table = export_table(&ir.root, None, &mut errors);
+    (table, errors)
+}
+
+fn export_table<L: Clone>(
+    table: &Table<Element<L>>,
+    default: Option<&Expr<L>>,
+    errors: &mut Vec<Error<L>>,
+) -> Table<Expr<L>> {
+    let mut res = Table::new();
+    if let Some(Expr {
+        kind: ExprKind::Table(ref default),
+        ..
+    }) = default
+    {
+        for (key, expr) in default.global.iter() {
+            if let Some(expr) = export_expr(expr, errors) {
+                res.global.insert(key.clone(), expr);
+            }
+        }
+    }
+
+    for (key, elem) in table.global.iter() {
+        let expr = match elem.kind {
+            ElementKind::Table(ref table, ref default) => Expr {
+                kind: ExprKind::Table(export_table(table, default.as_ref(), errors)),
+                span: elem.defined.clone(),
+            },
+            ElementKind::Array(ref arr, ref default) => Expr {
+                kind: ExprKind::Array(
+                    arr.iter()
+                        .map(|table| Expr {
+                            kind: ExprKind::Table(export_table(table, default.as_ref(), errors)),
+                            span: elem.defined.clone(),
+                        })
+                        .collect(),
+                ),
+                span: elem.defined.clone(),
+            },
+            // Functions are reported at the bound key.
+            ElementKind::Expr(Expr {
+                kind: ExprKind::Function(_),
+                ..
+            }) => {
+                errors.push(Error::FunctionNotRenderable {
+                    span: elem.defined.clone(),
+                });
+                continue;
+            }
+            ElementKind::Expr(ref expr) => match export_expr(expr, errors) {
+                Some(expr) => expr,
+                None => continue,
+            },
+        };
+        res.global.insert(key.clone(), expr);
+    }
+    res
+}
+
+fn export_expr<L: Clone>(expr: &Expr<L>, errors: &mut Vec<Error<L>>) -> Option<Expr<L>> {
     let kind = match expr.kind {
-        ExpressionKind::Literal(lit) => ExprKind::Literal(lit),
-        ExpressionKind::Array(arr) => ExprKind::Array(
-            arr.into_iter()
-                .map(|elem| expression(elem, errors))
+        ExprKind::Literal(ref lit) => ExprKind::Literal(lit.clone()),
+        ExprKind::Array(ref arr) => ExprKind::Array(
+            arr.iter()
+                .filter_map(|expr| export_expr(expr, errors))
                 .collect(),
         ),
-        ExpressionKind::InlineTable(arr) => {
-            let mut table = Table::new();
-            for (key, expr) in arr {
-                insert_expr(&mut table, key, expression(expr, errors), errors);
+        ExprKind::Table(ref table) => {
+            let mut res = Table::new();
+            for (key, expr) in table.global.iter() {
+                if let Some(expr) = export_expr(expr, errors) {
+                    res.global.insert(key.clone(), expr);
+                }
             }
-            ExprKind::Table(table)
+            ExprKind::Table(res)
         }
-        _ => {
-            errors.push(Error::NotSupported {
-                feature: "unknown expressions",
+        ExprKind::Function(_) => {
+            errors.push(Error::FunctionNotRenderable {
                 span: expr.span.clone(),
             });
-            ExprKind::Table(Table::new())
+            return None;
         }
     };
 
-    Expr {
+    Some(Expr {
         kind,
-        span: expr.span,
+        span: expr.span.clone(),
+    })
+}
+
+fn builtin_read<L: Clone>(key: Key<L>) -> Result<ExprKind<L>, Error<L>> {
+    if key.kind != KeyKind::Normal {
+        return Err(Error::BuiltinNotFound { span: key.span });
     }
+
+    Ok(ExprKind::Literal(match key.name.as_str() {
+        "true" => Literal::Boolean(true),
+        "false" => Literal::Boolean(false),
+        "null" => Literal::Null,
+        "inf" => Literal::Float(f64::INFINITY),
+        "nan" => Literal::Float(f64::NAN),
+        "map" | "filter" => return Ok(builtin_function(key)),
+        "output" | "filetype" => return Err(Error::BuiltinWriteOnly { span: key.span }),
+        _ => return Err(Error::BuiltinNotFound { span: key.span }),
+    }))
+}
+
+/// Makes a built-in function value, defined at the reference.
+fn builtin_function<L: Clone>(key: Key<L>) -> ExprKind<L> {
+    let builtin = match key.name.as_str() {
+        "map" => BuiltinFunction::Map,
+        _ => BuiltinFunction::Filter,
+    };
+    let param = |name: &str| Key {
+        kind: KeyKind::Normal,
+        name: String::from(name),
+        span: key.span.clone(),
+    };
+
+    ExprKind::Function(Box::new(Function {
+        parameters: vec![param("array"), param("function")],
+        body: FunctionBody::Builtin(builtin),
+        scope: Vec::new(),
+        captured: Table::new(),
+        defined: key.span,
+    }))
 }
 
 fn insert_expr<L: Clone>(
@@ -301,3 +1307,189 @@ fn insert_elem<L: Clone>(
         );
     }
 }
+
+/// Gets a table under the key, or creates an empty table if not exists.
+fn sub_table<'a, L: Clone>(
+    table: &'a mut Table<Element<L>>,
+    key: Key<L>,
+    errors: &mut Vec<Error<L>>,
+) -> Option<&'a mut Table<Element<L>>> {
+    let table = match key.kind {
+        KeyKind::Normal => &mut table.global,
+        KeyKind::Local => &mut table.local,
+        _ => {
+            errors.push(Error::NotSupported {
+                feature: "unknown keys",
+                span: key.span,
+            });
+            return None;
+        }
+    };
+
+    let elem = table.entry(key.name).or_insert_with(|| Element {
+        kind: ElementKind::Table(Table::new(), None),
+        defined: key.span.clone(),
+    });
+    match elem.kind {
+        ElementKind::Table(ref mut table, _) => Some(table),
+        // The last table of an array is extended, like TOML.
+        ElementKind::Array(ref mut arr, _) if !arr.is_empty() => arr.last_mut(),
+        _ => {
+            errors.push(Error::DuplicateKey {
+                found: key.span,
+                existing: Some(elem.defined.clone()),
+            });
+            None
+        }
+    }
+}
+
+/// Moves values from a table to an implicitly created table.
+fn extend_table<L: Clone>(
+    table: &mut Table<Element<L>>,
+    other: Table<Element<L>>,
+    errors: &mut Vec<Error<L>>,
+) {
+    for (existing, other) in [
+        (&mut table.global, other.global),
+        (&mut table.local, other.local),
+    ] {
+        for (name, elem) in other {
+            match existing.get(&name) {
+                Some(existing) => errors.push(Error::DuplicateKey {
+                    found: elem.defined,
+                    existing: Some(existing.defined.clone()),
+                }),
+                None => {
+                    existing.insert(name, elem);
+                }
+            }
+        }
+    }
+}
+
+fn get_elem<'a, L>(table: &'a Table<Element<L>>, key: &Key<L>) -> Option<&'a Element<L>> {
+    match key.kind {
+        KeyKind::Normal => table.global.get(&key.name),
+        KeyKind::Local => table.local.get(&key.name),
+        _ => None,
+    }
+}
+
+fn get_mut_elem<'a, L>(
+    table: &'a mut Table<Element<L>>,
+    key: &Key<L>,
+) -> Option<&'a mut Element<L>> {
+    match key.kind {
+        KeyKind::Normal => table.global.get_mut(&key.name),
+        KeyKind::Local => table.local.get_mut(&key.name),
+        _ => None,
+    }
+}
+
+fn get_expr<'a, L>(table: &'a Table<Expr<L>>, key: &Key<L>) -> Option<&'a Expr<L>> {
+    match key.kind {
+        KeyKind::Normal => table.global.get(&key.name),
+        KeyKind::Local => table.local.get(&key.name),
+        _ => None,
+    }
+}
+
+/// Gets a copy of the value in the table under the keys, including initial tables.
+fn lookup<L: Clone>(
+    table: &Table<Element<L>>,
+    path: &[Key<L>],
+    key: &Key<L>,
+) -> Option<ExprKind<L>> {
+    let mut table = table;
+    let mut default = None;
+    for k in path {
+        (table, default) = match get_elem(table, k)?.kind {
+            ElementKind::Table(ref table, ref default) => (table, default.as_ref()),
+            ElementKind::Array(ref arr, ref default) => (arr.last()?, default.as_ref()),
+            ElementKind::Expr(_) => return None,
+        };
+    }
+
+    if let Some(elem) = get_elem(table, key) {
+        return Some(element_expr(elem));
+    }
+    match default {
+        Some(Expr {
+            kind: ExprKind::Table(ref table),
+            ..
+        }) => get_expr(table, key).map(|expr| expr.kind.clone()),
+        _ => None,
+    }
+}
+
+/// Finds a local key defined in tables under the table, to report inaccessible references.
+fn find_local<L: Clone>(table: &Table<Element<L>>, name: &str) -> Option<Range<L>> {
+    table
+        .global
+        .values()
+        .chain(table.local.values())
+        .find_map(|elem| match elem.kind {
+            ElementKind::Table(ref table, _) => table
+                .local
+                .get(name)
+                .map(|elem| elem.defined.clone())
+                .or_else(|| find_local(table, name)),
+            ElementKind::Array(ref arr, _) => arr.iter().find_map(|table| {
+                table
+                    .local
+                    .get(name)
+                    .map(|elem| elem.defined.clone())
+                    .or_else(|| find_local(table, name))
+            }),
+            ElementKind::Expr(_) => None,
+        })
+}
+
+/// Converts an element to a value.
+fn element_expr<L: Clone>(elem: &Element<L>) -> ExprKind<L> {
+    match elem.kind {
+        ElementKind::Expr(ref expr) => expr.kind.clone(),
+        ElementKind::Table(ref table, ref default) => {
+            ExprKind::Table(table_expr(table, default.as_ref()))
+        }
+        ElementKind::Array(ref arr, ref default) => ExprKind::Array(
+            arr.iter()
+                .map(|table| Expr {
+                    kind: ExprKind::Table(table_expr(table, default.as_ref())),
+                    span: elem.defined.clone(),
+                })
+                .collect(),
+        ),
+    }
+}
+
+fn table_expr<L: Clone>(table: &Table<Element<L>>, default: Option<&Expr<L>>) -> Table<Expr<L>> {
+    let mut res = match default {
+        Some(Expr {
+            kind: ExprKind::Table(ref table),
+            ..
+        }) => table.clone(),
+        _ => Table::new(),
+    };
+
+    for (key, elem) in table.global.iter() {
+        res.global.insert(
+            key.clone(),
+            Expr {
+                kind: element_expr(elem),
+                span: elem.defined.clone(),
+            },
+        );
+    }
+    for (key, elem) in table.local.iter() {
+        res.local.insert(
+            key.clone(),
+            Expr {
+                kind: element_expr(elem),
+                span: elem.defined.clone(),
+            },
+        );
+    }
+    res
+}
@@ -0,0 +1,209 @@
+//! Evaluating operators
+use core::cmp::Ordering;
+use core::ops::Range;
+use drake_types::ast::{BinaryOpKind, Literal, UnaryOpKind};
+use drake_types::error::Error;
+use drake_types::ir::{Expr, ExprKind};
+
+/// Evaluates a binary operation except logical ones.
+pub fn binary<L: Clone>(
+    kind: BinaryOpKind,
+    lhs: Expr<L>,
+    rhs: Expr<L>,
+    span: Range<L>,
+) -> Result<ExprKind<L>, Error<L>> {
+    use BinaryOpKind::*;
+
+    match kind {
+        Add | Sub | Mul | Div | Rem => arithmetic(kind, lhs, rhs, span),
+        Eq | Ne => match equals(&lhs.kind, &rhs.kind) {
+            Some(eq) => Ok(boolean(eq == (kind == Eq))),
+            None => Err(mismatch(type_name(&lhs.kind), rhs.span)),
+        },
+        Lt | Le | Gt | Ge => {
+            use core::cmp::Ordering::*;
+            let ord = compare(&lhs, &rhs)?;
+            Ok(boolean(match kind {
+                Lt => ord == Some(Less),
+                Le => matches!(ord, Some(Less | Equal)),
+                Gt => ord == Some(Greater),
+                Ge => matches!(ord, Some(Greater | Equal)),
+                _ => return Err(Error::Unexpected),
+            }))
+        }
+        _ => Err(Error::Unexpected),
+    }
+}
+
+fn arithmetic<L: Clone>(
+    kind: BinaryOpKind,
+    lhs: Expr<L>,
+    rhs: Expr<L>,
+    span: Range<L>,
+) -> Result<ExprKind<L>, Error<L>> {
+    use BinaryOpKind::*;
+    use ExprKind::{Array, Literal as Lit};
+    use Literal::*;
+
+    if let (Some(a), Some(b)) = (integer(&lhs.kind), integer(&rhs.kind)) {
+        let res = match kind {
+            Add => a.checked_add(b),
+            Sub => a.checked_sub(b),
+            Mul => a.checked_mul(b),
+            Div | Rem if b == 0 => return Err(Error::DivisionByZero { span: rhs.span }),
+            Div => a.checked_div(b),
+            Rem => a.checked_rem(b),
+            _ => return Err(Error::Unexpected),
+        };
+        return res.and_then(from_integer).ok_or(Error::Overflow { span });
+    }
+
+    Ok(match (kind, lhs.kind, rhs.kind) {
+        (_, Lit(Float(a)), Lit(Float(b))) => Lit(Float(match kind {
+            Add => a + b,
+            Sub => a - b,
+            Mul => a * b,
+            Div => a / b,
+            Rem => a % b,
+            _ => return Err(Error::Unexpected),
+        })),
+        (Add, Lit(String(mut a)), Lit(String(b))) => {
+            a.push_str(&b);
+            Lit(String(a))
+        }
+        (Add, Array(mut a), Array(mut b)) => {
+            a.append(&mut b);
+            Array(a)
+        }
+        (_, Lit(Integer(_) | NegativeInteger(_)), _) => return Err(mismatch("integer", rhs.span)),
+        (_, Lit(Float(_)), _) => return Err(mismatch("float", rhs.span)),
+        (Add, Lit(String(_)), _) => return Err(mismatch("string", rhs.span)),
+        (Add, Array(_), _) => return Err(mismatch("array", rhs.span)),
+        (Add, _, _) => return Err(mismatch("number, string, or array", lhs.span)),
+        _ => return Err(mismatch("number", lhs.span)),
+    })
+}
+
+/// Gets the value of an integer in the wider type.
+fn integer<L>(kind: &ExprKind<L>) -> Option<i128> {
+    match kind {
+        ExprKind::Literal(Literal::Integer(i)) => Some(*i as i128),
+        ExprKind::Literal(Literal::NegativeInteger(i)) => Some(*i as i128),
+        _ => None,
+    }
+}
+
+/// Converts an integer to a literal, or returns `None` if it is out of range.
+fn from_integer<L>(i: i128) -> Option<ExprKind<L>> {
+    let lit = if i < 0 {
+        Literal::NegativeInteger(i64::try_from(i).ok()?)
+    } else {
+        Literal::Integer(u64::try_from(i).ok()?)
+    };
+    Some(ExprKind::Literal(lit))
+}
+
+/// Evaluates an unary operation.
+pub fn unary<L>(kind: UnaryOpKind, expr: Expr<L>, span: Range<L>) -> Result<ExprKind<L>, Error<L>> {
+    match (kind, expr.kind) {
+        (
+            UnaryOpKind::Neg,
+            ref kind @ ExprKind::Literal(Literal::Integer(_) | Literal::NegativeInteger(_)),
+        ) => integer(kind)
+            .and_then(|i| from_integer(-i))
+            .ok_or(Error::Overflow { span }),
+        (UnaryOpKind::Neg, ExprKind::Literal(Literal::Float(f))) => {
+            Ok(ExprKind::Literal(Literal::Float(-f)))
+        }
+        (
+            UnaryOpKind::Pos,
+            kind @ ExprKind::Literal(
+                Literal::Integer(_) | Literal::NegativeInteger(_) | Literal::Float(_),
+            ),
+        ) => Ok(kind),
+        (UnaryOpKind::Neg | UnaryOpKind::Pos, _) => Err(mismatch("number", expr.span)),
+        (UnaryOpKind::Not, ExprKind::Literal(Literal::Boolean(b))) => Ok(boolean(!b)),
+        (UnaryOpKind::Not, _) => Err(mismatch("boolean", expr.span)),
+        _ => Err(Error::Unexpected),
+    }
+}
+
+/// Checks equality of values, or returns `None` if the types are different.
+fn equals<L>(lhs: &ExprKind<L>, rhs: &ExprKind<L>) -> Option<bool> {
+    use ExprKind::{Array, Literal as Lit, Table};
+    use Literal::*;
+
+    if let (Some(a), Some(b)) = (integer(lhs), integer(rhs)) {
+        return Some(a == b);
+    }
+
+    match (lhs, rhs) {
+        (Lit(Float(a)), Lit(Float(b))) => Some(a == b),
+        (Lit(Character(a)), Lit(Character(b))) => Some(a == b),
+        (Lit(String(a)), Lit(String(b))) => Some(a == b),
+        (Lit(Boolean(a)), Lit(Boolean(b))) => Some(a == b),
+        (Lit(Null), Lit(Null)) => Some(true),
+        (Array(a), Array(b)) => Some(
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b.iter())
+                    .all(|(a, b)| equals(&a.kind, &b.kind) == Some(true)),
+        ),
+        // Local keys are not a part of the value.
+        (Table(a), Table(b)) => Some(
+            a.global.len() == b.global.len()
+                && a.global.iter().all(|(key, a)| {
+                    b.global
+                        .get(key)
+                        .is_some_and(|b| equals(&a.kind, &b.kind) == Some(true))
+                }),
+        ),
+        _ => None,
+    }
+}
+
+/// Compares values, or returns `None` for unordered floats.
+fn compare<L: Clone>(lhs: &Expr<L>, rhs: &Expr<L>) -> Result<Option<Ordering>, Error<L>> {
+    use ExprKind::Literal as Lit;
+    use Literal::*;
+
+    if let (Some(a), Some(b)) = (integer(&lhs.kind), integer(&rhs.kind)) {
+        return Ok(Some(a.cmp(&b)));
+    }
+
+    match (&lhs.kind, &rhs.kind) {
+        (Lit(Float(a)), Lit(Float(b))) => Ok(a.partial_cmp(b)),
+        (Lit(Character(a)), Lit(Character(b))) => Ok(Some(a.cmp(b))),
+        (Lit(String(a)), Lit(String(b))) => Ok(Some(a.cmp(b))),
+        (Lit(Integer(_) | NegativeInteger(_) | Float(_) | Character(_) | String(_)), _) => {
+            Err(mismatch(type_name(&lhs.kind), rhs.span.clone()))
+        }
+        _ => Err(mismatch("number, character, or string", lhs.span.clone())),
+    }
+}
+
+/// Gets the name of the type of the value.
+fn type_name<L>(kind: &ExprKind<L>) -> &'static str {
+    match kind {
+        ExprKind::Literal(Literal::Integer(_) | Literal::NegativeInteger(_)) => "integer",
+        ExprKind::Literal(Literal::Float(_)) => "float",
+        ExprKind::Literal(Literal::Character(_)) => "character",
+        ExprKind::Literal(Literal::String(_)) => "string",
+        ExprKind::Literal(Literal::Boolean(_)) => "boolean",
+        ExprKind::Literal(Literal::Null) => "null",
+        ExprKind::Array(_) => "array",
+        ExprKind::Table(_) => "table",
+        ExprKind::Function(_) => "function",
+        _ => "value",
+    }
+}
+
+#[inline]
+pub fn boolean<L>(b: bool) -> ExprKind<L> {
+    ExprKind::Literal(Literal::Boolean(b))
+}
+
+#[inline]
+pub fn mismatch<L>(expected: &'static str, span: Range<L>) -> Error<L> {
+    Error::TypeMismatch { expected, span }
+}
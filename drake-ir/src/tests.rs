@@ -0,0 +1,1895 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+use drake_types::ast::{
+    BinaryOpKind, Expression, ExpressionKind, Key, KeyKind, KeyPattern, Literal, Pattern,
+    PatternKind, Statement, StatementKind, TableHeaderKind, UnaryOpKind,
+};
+use drake_types::error::Error;
+use drake_types::ir::{ElementKind, Expr, ExprKind, Ir, Table};
+
+use super::{export, interpret};
+
+/// Makes a key, which is local if the name starts with `_`.
+fn key(name: &str, span: Range<usize>) -> Key<usize> {
+    match name.strip_prefix('_') {
+        Some(name) => Key {
+            kind: KeyKind::Local,
+            name: String::from(name),
+            span,
+        },
+        None => Key {
+            kind: KeyKind::Normal,
+            name: String::from(name),
+            span,
+        },
+    }
+}
+
+fn expr(kind: ExpressionKind<usize>, span: Range<usize>) -> Expression<usize> {
+    Expression { kind, span }
+}
+
+fn lit(lit: Literal, span: Range<usize>) -> Expression<usize> {
+    expr(ExpressionKind::Literal(lit), span)
+}
+
+fn int(i: u64, span: Range<usize>) -> Expression<usize> {
+    lit(Literal::Integer(i), span)
+}
+
+fn reference(name: &str, span: Range<usize>) -> Expression<usize> {
+    expr(ExpressionKind::Key(key(name, span.clone())), span)
+}
+
+fn array(elems: Vec<Expression<usize>>, span: Range<usize>) -> Expression<usize> {
+    expr(ExpressionKind::Array(elems), span)
+}
+
+fn table(pairs: Vec<(Key<usize>, Expression<usize>)>, span: Range<usize>) -> Expression<usize> {
+    expr(ExpressionKind::InlineTable(pairs), span)
+}
+
+fn root(name: &str, span: Range<usize>) -> Expression<usize> {
+    let key = key(name, span.start + 1..span.end);
+    expr(ExpressionKind::Root(key), span)
+}
+
+/// Makes a built-in reference like `@true`, whose key starts after `@`.
+fn builtin(name: &str, span: Range<usize>) -> Expression<usize> {
+    let key = key(name, span.start + 1..span.end);
+    expr(ExpressionKind::Builtin(key), span)
+}
+
+fn binary(kind: BinaryOpKind, lhs: Expression<usize>, rhs: Expression<usize>) -> Expression<usize> {
+    let span = lhs.span.start..rhs.span.end;
+    expr(
+        ExpressionKind::BinaryOp(kind, Box::new(lhs), Box::new(rhs)),
+        span,
+    )
+}
+
+fn unary(kind: UnaryOpKind, operand: Expression<usize>, start: usize) -> Expression<usize> {
+    let span = start..operand.span.end;
+    expr(ExpressionKind::UnaryOp(kind, Box::new(operand)), span)
+}
+
+fn boolean(b: bool, span: Range<usize>) -> Expression<usize> {
+    lit(Literal::Boolean(b), span)
+}
+
+fn field(expr: Expression<usize>, name: &str, span: Range<usize>) -> Expression<usize> {
+    let whole = expr.span.start..span.end;
+    self::expr(
+        ExpressionKind::Field(Box::new(expr), key(name, span)),
+        whole,
+    )
+}
+
+fn index(expr: Expression<usize>, index: Expression<usize>, end: usize) -> Expression<usize> {
+    let span = expr.span.start..end;
+    self::expr(ExpressionKind::Index(Box::new(expr), Box::new(index)), span)
+}
+
+/// Makes a call like `f(a, key = b)`, ending at `end`.
+fn call(
+    func: Expression<usize>,
+    args: Vec<Expression<usize>>,
+    named: Vec<(Key<usize>, Expression<usize>)>,
+    end: usize,
+) -> Expression<usize> {
+    let span = func.span.start..end;
+    expr(ExpressionKind::Call(Box::new(func), args, named), span)
+}
+
+/// Makes an inline function like `|a| a`, starting at `start`.
+fn inline(params: Vec<Key<usize>>, body: Expression<usize>, start: usize) -> Expression<usize> {
+    let span = start..body.span.end;
+    expr(ExpressionKind::InlineFunction(params, Box::new(body)), span)
+}
+
+fn pattern(name: &str, span: Range<usize>) -> Pattern<usize> {
+    Pattern {
+        kind: PatternKind::Key(key(name, span.clone())),
+        span,
+    }
+}
+
+fn dotted(keys: Vec<Key<usize>>) -> Pattern<usize> {
+    let span = keys[0].span.start..keys[keys.len() - 1].span.end;
+    Pattern {
+        kind: PatternKind::Dotted(keys),
+        span,
+    }
+}
+
+/// Makes a root key pattern like `.a.b`, starting at the dot before the keys.
+fn root_pattern(keys: Vec<Key<usize>>) -> Pattern<usize> {
+    let span = keys[0].span.start - 1..keys[keys.len() - 1].span.end;
+    Pattern {
+        kind: PatternKind::Root(keys),
+        span,
+    }
+}
+
+fn array_pattern(
+    pats: Vec<Pattern<usize>>,
+    rest: Option<Pattern<usize>>,
+    span: Range<usize>,
+) -> Pattern<usize> {
+    Pattern {
+        kind: PatternKind::Array(pats, rest.map(Box::new)),
+        span,
+    }
+}
+
+fn table_pattern(keys: Vec<KeyPattern<usize>>, span: Range<usize>) -> Pattern<usize> {
+    Pattern {
+        kind: PatternKind::Table(keys),
+        span,
+    }
+}
+
+fn key_pattern(
+    key: Key<usize>,
+    pattern: Option<Pattern<usize>>,
+    default: Option<Expression<usize>>,
+) -> KeyPattern<usize> {
+    KeyPattern {
+        key,
+        pattern,
+        default,
+    }
+}
+
+fn builtin_pattern(name: &str, span: Range<usize>) -> Pattern<usize> {
+    Pattern {
+        kind: PatternKind::Builtin(key(name, span.start + 1..span.end)),
+        span,
+    }
+}
+
+fn bind(pattern: Pattern<usize>, expr: Expression<usize>) -> Statement<usize> {
+    let span = pattern.span.start..expr.span.end;
+    Statement {
+        kind: StatementKind::ValueBinding(pattern, expr),
+        span,
+    }
+}
+
+fn header(pattern: Pattern<usize>) -> Statement<usize> {
+    Statement {
+        span: pattern.span.clone(),
+        kind: StatementKind::TableHeader(TableHeaderKind::Normal, pattern, None),
+    }
+}
+
+/// Makes a table header with an initial table like `[a = b]`.
+fn default_header(
+    kind: TableHeaderKind,
+    pattern: Pattern<usize>,
+    default: Expression<usize>,
+) -> Statement<usize> {
+    Statement {
+        span: pattern.span.start..default.span.end,
+        kind: StatementKind::TableHeader(kind, pattern, Some(default)),
+    }
+}
+
+/// Makes an expression function like `fn f(a) = a`, starting at `fn` before the key.
+fn function(key: Key<usize>, params: Vec<Key<usize>>, body: Expression<usize>) -> Statement<usize> {
+    Statement {
+        span: key.span.start - 3..body.span.end,
+        kind: StatementKind::ExpressionFunction(key, params, body),
+    }
+}
+
+/// Makes a table function header like `[fn f(a)]`, starting at `fn` before the key.
+fn table_function(key: Key<usize>, params: Vec<Key<usize>>) -> Statement<usize> {
+    let end = params.last().map_or(key.span.end, |param| param.span.end) + 1;
+    Statement {
+        span: key.span.start - 3..end,
+        kind: StatementKind::TableFunction(key, params),
+    }
+}
+
+/// Gets the value bound to the keys from the root table.
+fn value<'a>(ir: &'a Ir<usize>, path: &[&str]) -> &'a ExprKind<usize> {
+    let (last, path) = path.split_last().unwrap();
+    let mut table = &ir.root;
+    for key in path {
+        table = match table.global[*key].kind {
+            ElementKind::Table(ref table, _) => table,
+            ElementKind::Array(ref arr, _) => arr.last().unwrap(),
+            ElementKind::Expr(_) => panic!("`{key}` is not a table"),
+        };
+    }
+    match table.global[*last].kind {
+        ElementKind::Expr(ref expr) => &expr.kind,
+        _ => panic!("`{last}` is not a value"),
+    }
+}
+
+/// Gets the value bound to the keys from an exported table.
+fn exported<'a>(table: &'a Table<Expr<usize>>, path: &[&str]) -> &'a ExprKind<usize> {
+    let (last, path) = path.split_last().unwrap();
+    let mut table = table;
+    for key in path {
+        table = match table.global[*key].kind {
+            ExprKind::Table(ref table) => table,
+            ref kind => panic!("{kind:?} is not a table"),
+        };
+    }
+    &table.global[*last].kind
+}
+
+fn literal(lit: Literal) -> ExprKind<usize> {
+    ExprKind::Literal(lit)
+}
+
+/// Gets the literals in an array.
+fn literals(kind: &ExprKind<usize>) -> Vec<&Literal> {
+    match kind {
+        ExprKind::Array(arr) => arr
+            .iter()
+            .map(|expr| match expr.kind {
+                ExprKind::Literal(ref lit) => lit,
+                ref kind => panic!("{kind:?} is not a literal"),
+            })
+            .collect(),
+        kind => panic!("{kind:?} is not an array"),
+    }
+}
+
+#[test]
+fn reference_scope() {
+    let (ir, errors) = interpret(&[
+        // _base = 1
+        bind(pattern("_base", 0..5), int(1, 8..9)),
+        // a = _base
+        bind(pattern("a", 10..11), reference("_base", 14..19)),
+        // [t]
+        header(pattern("t", 21..22)),
+        // b = _base
+        bind(pattern("b", 24..25), reference("_base", 28..33)),
+        // c = .a
+        bind(
+            pattern("c", 34..35),
+            expr(ExpressionKind::Root(key("a", 39..40)), 38..40),
+        ),
+        // _x = 2
+        bind(pattern("_x", 41..43), int(2, 46..47)),
+        // d = _x
+        bind(pattern("d", 48..49), reference("_x", 52..54)),
+    ]);
+    assert_eq!(errors, Vec::new());
+    assert_eq!(value(&ir, &["a"]), &literal(Literal::Integer(1)));
+    assert_eq!(value(&ir, &["t", "b"]), &literal(Literal::Integer(1)));
+    assert_eq!(value(&ir, &["t", "c"]), &literal(Literal::Integer(1)));
+    assert_eq!(value(&ir, &["t", "d"]), &literal(Literal::Integer(2)));
+}
+
+#[test]
+fn reference_errors() {
+    let (_, errors) = interpret(&[
+        // a = 1
+        bind(pattern("a", 0..1), int(1, 4..5)),
+        // b = c
+        bind(pattern("b", 6..7), reference("c", 10..11)),
+        // [t]
+        header(pattern("t", 13..14)),
+        // _x = 1
+        bind(pattern("_x", 16..18), int(1, 21..22)),
+        // b = a
+        bind(pattern("b", 23..24), reference("a", 27..28)),
+        // [u]
+        header(pattern("u", 30..31)),
+        // y = _x
+        bind(pattern("y", 33..34), reference("_x", 37..39)),
+        // z = .w
+        bind(
+            pattern("z", 40..41),
+            expr(ExpressionKind::Root(key("w", 45..46)), 44..46),
+        ),
+    ]);
+    assert_eq!(
+        errors,
+        vec![
+            Error::KeyNotFound { span: 10..11 },
+            // Normal keys are not looked up in outer scopes.
+            Error::KeyNotFound { span: 27..28 },
+            Error::InaccessibleKey {
+                found: 37..39,
+                defined: 16..18,
+            },
+            Error::KeyNotFound { span: 45..46 },
+        ]
+    );
+}
+
+#[test]
+fn arithmetic() {
+    use BinaryOpKind::*;
+
+    let (ir, errors) = interpret(&[
+        // a = 1 + 2 * 3
+        bind(
+            pattern("a", 0..1),
+            binary(Add, int(1, 4..5), binary(Mul, int(2, 8..9), int(3, 12..13))),
+        ),
+        // b = 3 - 5
+        bind(
+            pattern("b", 14..15),
+            binary(Sub, int(3, 18..19), int(5, 22..23)),
+        ),
+        // c = -5 + 18446744073709551615
+        bind(
+            pattern("c", 24..25),
+            binary(
+                Add,
+                lit(Literal::NegativeInteger(-5), 28..30),
+                int(u64::MAX, 33..53),
+            ),
+        ),
+        // d = -7 / 2
+        bind(
+            pattern("d", 54..55),
+            binary(
+                Div,
+                lit(Literal::NegativeInteger(-7), 58..60),
+                int(2, 63..64),
+            ),
+        ),
+        // e = -7 % 2
+        bind(
+            pattern("e", 65..66),
+            binary(
+                Rem,
+                lit(Literal::NegativeInteger(-7), 69..71),
+                int(2, 74..75),
+            ),
+        ),
+        // f = 0.5 * 0.25
+        bind(
+            pattern("f", 76..77),
+            binary(
+                Mul,
+                lit(Literal::Float(0.5), 80..83),
+                lit(Literal::Float(0.25), 86..90),
+            ),
+        ),
+        // g = "a" + "b"
+        bind(
+            pattern("g", 91..92),
+            binary(
+                Add,
+                lit(Literal::String(String::from("a")), 95..98),
+                lit(Literal::String(String::from("b")), 101..104),
+            ),
+        ),
+        // h = [1] + [2]
+        bind(
+            pattern("h", 105..106),
+            binary(
+                Add,
+                expr(ExpressionKind::Array(vec![int(1, 110..111)]), 109..112),
+                expr(ExpressionKind::Array(vec![int(2, 116..117)]), 115..118),
+            ),
+        ),
+    ]);
+    assert_eq!(errors, Vec::new());
+    assert_eq!(value(&ir, &["a"]), &literal(Literal::Integer(7)));
+    assert_eq!(value(&ir, &["b"]), &literal(Literal::NegativeInteger(-2)));
+    assert_eq!(value(&ir, &["c"]), &literal(Literal::Integer(u64::MAX - 5)));
+    assert_eq!(value(&ir, &["d"]), &literal(Literal::NegativeInteger(-3)));
+    assert_eq!(value(&ir, &["e"]), &literal(Literal::NegativeInteger(-1)));
+    assert_eq!(value(&ir, &["f"]), &literal(Literal::Float(0.125)));
+    assert_eq!(
+        value(&ir, &["g"]),
+        &literal(Literal::String(String::from("ab")))
+    );
+    match value(&ir, &["h"]) {
+        ExprKind::Array(arr) => assert_eq!(
+            arr.iter().map(|expr| &expr.kind).collect::<Vec<_>>(),
+            [&literal(Literal::Integer(1)), &literal(Literal::Integer(2))]
+        ),
+        kind => panic!("{kind:?} is not an array"),
+    }
+}
+
+#[test]
+fn arithmetic_errors() {
+    use BinaryOpKind::*;
+
+    let (_, errors) = interpret(&[
+        // a = 18446744073709551615 + 1
+        bind(
+            pattern("a", 0..1),
+            binary(Add, int(u64::MAX, 4..24), int(1, 27..28)),
+        ),
+        // b = -9223372036854775808 - 1
+        bind(
+            pattern("b", 29..30),
+            binary(
+                Sub,
+                lit(Literal::NegativeInteger(i64::MIN), 33..53),
+                int(1, 56..57),
+            ),
+        ),
+        // c = 1 / 0
+        bind(
+            pattern("c", 58..59),
+            binary(Div, int(1, 62..63), int(0, 66..67)),
+        ),
+        // d = 1 % 0
+        bind(
+            pattern("d", 68..69),
+            binary(Rem, int(1, 72..73), int(0, 76..77)),
+        ),
+        // e = 1 + "a"
+        bind(
+            pattern("e", 78..79),
+            binary(
+                Add,
+                int(1, 82..83),
+                lit(Literal::String(String::from("a")), 86..89),
+            ),
+        ),
+        // f = 1 / 0 + 1
+        bind(
+            pattern("f", 90..91),
+            binary(
+                Add,
+                binary(Div, int(1, 94..95), int(0, 98..99)),
+                int(1, 102..103),
+            ),
+        ),
+        // g = @true * 2
+        bind(
+            pattern("g", 104..105),
+            binary(Mul, lit(Literal::Boolean(true), 108..113), int(2, 116..117)),
+        ),
+    ]);
+    assert_eq!(
+        errors,
+        vec![
+            Error::Overflow { span: 4..28 },
+            Error::Overflow { span: 33..57 },
+            Error::DivisionByZero { span: 66..67 },
+            Error::DivisionByZero { span: 76..77 },
+            Error::TypeMismatch {
+                expected: "integer",
+                span: 86..89,
+            },
+            // Errors in operands are reported only once.
+            Error::DivisionByZero { span: 98..99 },
+            Error::TypeMismatch {
+                expected: "number",
+                span: 108..113,
+            },
+        ]
+    );
+}
+
+#[test]
+fn comparison() {
+    use BinaryOpKind::*;
+
+    let (ir, errors) = interpret(&[
+        // a = 1 < 2
+        bind(pattern("a", 0..1), binary(Lt, int(1, 4..5), int(2, 8..9))),
+        // b = 3 >= 3
+        bind(
+            pattern("b", 10..11),
+            binary(Ge, int(3, 14..15), int(3, 19..20)),
+        ),
+        // c = "a" > "b"
+        bind(
+            pattern("c", 21..22),
+            binary(
+                Gt,
+                lit(Literal::String(String::from("a")), 25..28),
+                lit(Literal::String(String::from("b")), 31..34),
+            ),
+        ),
+        // d = -1 == 18446744073709551615
+        bind(
+            pattern("d", 35..36),
+            binary(
+                Eq,
+                lit(Literal::NegativeInteger(-1), 39..41),
+                int(u64::MAX, 45..65),
+            ),
+        ),
+        // e = [1, 2] == [1, 2]
+        bind(
+            pattern("e", 66..67),
+            binary(
+                Eq,
+                expr(
+                    ExpressionKind::Array(vec![int(1, 71..72), int(2, 74..75)]),
+                    70..76,
+                ),
+                expr(
+                    ExpressionKind::Array(vec![int(1, 81..82), int(2, 84..85)]),
+                    80..86,
+                ),
+            ),
+        ),
+        // f = 'a' != 'a'
+        bind(
+            pattern("f", 87..88),
+            binary(
+                Ne,
+                lit(Literal::Character('a'), 91..94),
+                lit(Literal::Character('a'), 98..101),
+            ),
+        ),
+        // g = 0.5 <= 0.25
+        bind(
+            pattern("g", 102..103),
+            binary(
+                Le,
+                lit(Literal::Float(0.5), 106..109),
+                lit(Literal::Float(0.25), 113..117),
+            ),
+        ),
+    ]);
+    assert_eq!(errors, Vec::new());
+    assert_eq!(value(&ir, &["a"]), &literal(Literal::Boolean(true)));
+    assert_eq!(value(&ir, &["b"]), &literal(Literal::Boolean(true)));
+    assert_eq!(value(&ir, &["c"]), &literal(Literal::Boolean(false)));
+    assert_eq!(value(&ir, &["d"]), &literal(Literal::Boolean(false)));
+    assert_eq!(value(&ir, &["e"]), &literal(Literal::Boolean(true)));
+    assert_eq!(value(&ir, &["f"]), &literal(Literal::Boolean(false)));
+    assert_eq!(value(&ir, &["g"]), &literal(Literal::Boolean(false)));
+
+    let (_, errors) = interpret(&[
+        // a = 1 == "a"
+        bind(
+            pattern("a", 0..1),
+            binary(
+                Eq,
+                int(1, 4..5),
+                lit(Literal::String(String::from("a")), 9..12),
+            ),
+        ),
+        // b = 1 < 'a'
+        bind(
+            pattern("b", 13..14),
+            binary(Lt, int(1, 17..18), lit(Literal::Character('a'), 21..24)),
+        ),
+        // c = true < false
+        bind(
+            pattern("c", 25..26),
+            binary(Lt, boolean(true, 29..33), boolean(false, 36..41)),
+        ),
+    ]);
+    assert_eq!(
+        errors,
+        vec![
+            Error::TypeMismatch {
+                expected: "integer",
+                span: 9..12,
+            },
+            Error::TypeMismatch {
+                expected: "integer",
+                span: 21..24,
+            },
+            Error::TypeMismatch {
+                expected: "number, character, or string",
+                span: 29..33,
+            },
+        ]
+    );
+}
+
+#[test]
+fn logic() {
+    use BinaryOpKind::*;
+
+    let (ir, errors) = interpret(&[
+        // a = false && unknown
+        bind(
+            pattern("a", 0..1),
+            binary(And, boolean(false, 4..9), reference("unknown", 13..20)),
+        ),
+        // b = true || unknown
+        bind(
+            pattern("b", 21..22),
+            binary(Or, boolean(true, 25..29), reference("unknown", 33..40)),
+        ),
+        // c = true && false
+        bind(
+            pattern("c", 41..42),
+            binary(And, boolean(true, 45..49), boolean(false, 53..58)),
+        ),
+        // d = false || true
+        bind(
+            pattern("d", 59..60),
+            binary(Or, boolean(false, 63..68), boolean(true, 72..76)),
+        ),
+        // e = !true
+        bind(
+            pattern("e", 77..78),
+            unary(UnaryOpKind::Not, boolean(true, 82..86), 81),
+        ),
+    ]);
+    // The right-hand sides are not evaluated if the results are determined.
+    assert_eq!(errors, Vec::new());
+    assert_eq!(value(&ir, &["a"]), &literal(Literal::Boolean(false)));
+    assert_eq!(value(&ir, &["b"]), &literal(Literal::Boolean(true)));
+    assert_eq!(value(&ir, &["c"]), &literal(Literal::Boolean(false)));
+    assert_eq!(value(&ir, &["d"]), &literal(Literal::Boolean(true)));
+    assert_eq!(value(&ir, &["e"]), &literal(Literal::Boolean(false)));
+
+    let (_, errors) = interpret(&[
+        // a = true && unknown
+        bind(
+            pattern("a", 0..1),
+            binary(And, boolean(true, 4..8), reference("unknown", 12..19)),
+        ),
+        // b = 1 || true
+        bind(
+            pattern("b", 20..21),
+            binary(Or, int(1, 24..25), boolean(true, 29..33)),
+        ),
+        // c = true && 1
+        bind(
+            pattern("c", 34..35),
+            binary(And, boolean(true, 38..42), int(1, 46..47)),
+        ),
+        // d = !1
+        bind(
+            pattern("d", 48..49),
+            unary(UnaryOpKind::Not, int(1, 53..54), 52),
+        ),
+    ]);
+    assert_eq!(
+        errors,
+        vec![
+            Error::KeyNotFound { span: 12..19 },
+            Error::TypeMismatch {
+                expected: "boolean",
+                span: 24..25,
+            },
+            Error::TypeMismatch {
+                expected: "boolean",
+                span: 46..47,
+            },
+            Error::TypeMismatch {
+                expected: "boolean",
+                span: 53..54,
+            },
+        ]
+    );
+}
+
+#[test]
+fn builtins() {
+    let (ir, errors) = interpret(&[
+        // a = @true
+        bind(pattern("a", 0..1), builtin("true", 4..9)),
+        // b = @false
+        bind(pattern("b", 10..11), builtin("false", 14..20)),
+        // c = @null
+        bind(pattern("c", 21..22), builtin("null", 25..30)),
+        // d = @inf
+        bind(pattern("d", 31..32), builtin("inf", 35..39)),
+        // e = @nan
+        bind(pattern("e", 40..41), builtin("nan", 44..48)),
+        // @output = "out.json"
+        bind(
+            builtin_pattern("output", 49..56),
+            lit(Literal::String(String::from("out.json")), 59..69),
+        ),
+        // @filetype = "json"
+        bind(
+            builtin_pattern("filetype", 70..79),
+            lit(Literal::String(String::from("json")), 82..88),
+        ),
+    ]);
+    assert_eq!(errors, Vec::new());
+    assert_eq!(value(&ir, &["a"]), &literal(Literal::Boolean(true)));
+    assert_eq!(value(&ir, &["b"]), &literal(Literal::Boolean(false)));
+    assert_eq!(value(&ir, &["c"]), &literal(Literal::Null));
+    assert_eq!(value(&ir, &["d"]), &literal(Literal::Float(f64::INFINITY)));
+    assert!(matches!(
+        value(&ir, &["e"]),
+        ExprKind::Literal(Literal::Float(f)) if f.is_nan()
+    ));
+    assert_eq!(ir.builtin.output.map(|elem| elem.defined), Some(50..56));
+    assert_eq!(ir.builtin.filetype.map(|elem| elem.defined), Some(71..79));
+    // Built-ins are not a part of the root table.
+    assert_eq!(ir.root.global.len(), 5);
+}
+
+#[test]
+fn builtin_errors() {
+    let (_, errors) = interpret(&[
+        // @true = 1
+        bind(builtin_pattern("true", 0..5), int(1, 8..9)),
+        // @map = 1
+        bind(builtin_pattern("map", 10..14), int(1, 17..18)),
+        // a = @output
+        bind(pattern("a", 19..20), builtin("output", 23..30)),
+        // b = @unknown
+        bind(pattern("b", 31..32), builtin("unknown", 35..43)),
+        // @unknown = 1
+        bind(builtin_pattern("unknown", 44..52), int(1, 55..56)),
+        // @output = "a.json"
+        bind(
+            builtin_pattern("output", 57..64),
+            lit(Literal::String(String::from("a.json")), 67..75),
+        ),
+        // @output = "b.json"
+        bind(
+            builtin_pattern("output", 76..83),
+            lit(Literal::String(String::from("b.json")), 86..94),
+        ),
+    ]);
+    assert_eq!(
+        errors,
+        vec![
+            Error::BuiltinReadOnly { span: 1..5 },
+            Error::BuiltinReadOnly { span: 11..14 },
+            Error::BuiltinWriteOnly { span: 24..30 },
+            Error::BuiltinNotFound { span: 36..43 },
+            Error::BuiltinNotFound { span: 45..52 },
+            Error::DuplicateKey {
+                found: 77..83,
+                existing: Some(58..64),
+            },
+        ]
+    );
+}
+
+#[test]
+fn integer_range() {
+    use UnaryOpKind::*;
+
+    let (ir, errors) = interpret(&[
+        // a = -9223372036854775808
+        bind(pattern("a", 0..1), unary(Neg, int(1 << 63, 5..24), 4)),
+        // b = -(-9223372036854775808)
+        bind(
+            pattern("b", 25..26),
+            unary(Neg, lit(Literal::NegativeInteger(i64::MIN), 31..51), 29),
+        ),
+        // c = +18446744073709551615
+        bind(pattern("c", 53..54), unary(Pos, int(u64::MAX, 58..78), 57)),
+        // d = -0
+        bind(pattern("d", 79..80), unary(Neg, int(0, 84..85), 83)),
+        // e = -1.5
+        bind(
+            pattern("e", 86..87),
+            unary(Neg, lit(Literal::Float(1.5), 91..94), 90),
+        ),
+        // f = 9223372036854775807 + 9223372036854775808
+        bind(
+            pattern("f", 95..96),
+            binary(
+                BinaryOpKind::Add,
+                int(i64::MAX as u64, 99..118),
+                int(1 << 63, 121..140),
+            ),
+        ),
+    ]);
+    assert_eq!(errors, Vec::new());
+    assert_eq!(
+        value(&ir, &["a"]),
+        &literal(Literal::NegativeInteger(i64::MIN))
+    );
+    assert_eq!(value(&ir, &["b"]), &literal(Literal::Integer(1 << 63)));
+    assert_eq!(value(&ir, &["c"]), &literal(Literal::Integer(u64::MAX)));
+    assert_eq!(value(&ir, &["d"]), &literal(Literal::Integer(0)));
+    assert_eq!(value(&ir, &["e"]), &literal(Literal::Float(-1.5)));
+    assert_eq!(value(&ir, &["f"]), &literal(Literal::Integer(u64::MAX)));
+
+    let (_, errors) = interpret(&[
+        // a = -9223372036854775809
+        bind(pattern("a", 0..1), unary(Neg, int((1 << 63) + 1, 5..24), 4)),
+        // b = -18446744073709551615
+        bind(pattern("b", 25..26), unary(Neg, int(u64::MAX, 30..50), 29)),
+        // c = 18446744073709551615 * -1
+        bind(
+            pattern("c", 51..52),
+            binary(
+                BinaryOpKind::Mul,
+                int(u64::MAX, 55..75),
+                lit(Literal::NegativeInteger(-1), 78..80),
+            ),
+        ),
+        // d = -"a"
+        bind(
+            pattern("d", 81..82),
+            unary(Neg, lit(Literal::String(String::from("a")), 86..89), 85),
+        ),
+    ]);
+    assert_eq!(
+        errors,
+        vec![
+            Error::Overflow { span: 4..24 },
+            Error::Overflow { span: 29..50 },
+            Error::Overflow { span: 55..80 },
+            Error::TypeMismatch {
+                expected: "number",
+                span: 86..89,
+            },
+        ]
+    );
+}
+
+#[test]
+fn access() {
+    let (ir, errors) = interpret(&[
+        // t = { a = 1, "raw key" = 2, b = [10, 20] }
+        bind(
+            pattern("t", 0..1),
+            table(
+                vec![
+                    (key("a", 6..7), int(1, 10..11)),
+                    (key("raw key", 13..22), int(2, 25..26)),
+                    (
+                        key("b", 28..29),
+                        array(vec![int(10, 33..35), int(20, 37..39)], 32..40),
+                    ),
+                ],
+                4..42,
+            ),
+        ),
+        // a = t.a
+        bind(
+            pattern("a", 43..44),
+            field(reference("t", 47..48), "a", 49..50),
+        ),
+        // b = t["raw key"]
+        bind(
+            pattern("b", 51..52),
+            index(
+                reference("t", 55..56),
+                lit(Literal::String(String::from("raw key")), 57..66),
+                67,
+            ),
+        ),
+        // c = t.b[1]
+        bind(
+            pattern("c", 68..69),
+            index(
+                field(reference("t", 72..73), "b", 74..75),
+                int(1, 76..77),
+                78,
+            ),
+        ),
+        // d = [1, 2][0]
+        bind(
+            pattern("d", 79..80),
+            index(
+                array(vec![int(1, 84..85), int(2, 87..88)], 83..89),
+                int(0, 90..91),
+                92,
+            ),
+        ),
+    ]);
+    assert_eq!(errors, Vec::new());
+    assert_eq!(value(&ir, &["a"]), &literal(Literal::Integer(1)));
+    assert_eq!(value(&ir, &["b"]), &literal(Literal::Integer(2)));
+    assert_eq!(value(&ir, &["c"]), &literal(Literal::Integer(20)));
+    assert_eq!(value(&ir, &["d"]), &literal(Literal::Integer(1)));
+}
+
+#[test]
+fn access_errors() {
+    let t = |span: Range<usize>| reference("t", span);
+    let (_, errors) = interpret(&[
+        // t = { a = [1, 2] }
+        bind(
+            pattern("t", 0..1),
+            table(
+                vec![(
+                    key("a", 6..7),
+                    array(vec![int(1, 11..12), int(2, 14..15)], 10..16),
+                )],
+                4..18,
+            ),
+        ),
+        // b = t.b
+        bind(pattern("b", 19..20), field(t(23..24), "b", 25..26)),
+        // c = t["c"]
+        bind(
+            pattern("c", 27..28),
+            index(
+                t(31..32),
+                lit(Literal::String(String::from("c")), 33..36),
+                37,
+            ),
+        ),
+        // d = t.a[2]
+        bind(
+            pattern("d", 38..39),
+            index(field(t(42..43), "a", 44..45), int(2, 46..47), 48),
+        ),
+        // e = t.a[-1]
+        bind(
+            pattern("e", 49..50),
+            index(
+                field(t(53..54), "a", 55..56),
+                lit(Literal::NegativeInteger(-1), 57..59),
+                60,
+            ),
+        ),
+        // f = t.a["a"]
+        bind(
+            pattern("f", 61..62),
+            index(
+                field(t(65..66), "a", 67..68),
+                lit(Literal::String(String::from("a")), 69..72),
+                73,
+            ),
+        ),
+        // g = t[0]
+        bind(pattern("g", 74..75), index(t(78..79), int(0, 80..81), 82)),
+        // h = 1.a
+        bind(pattern("h", 83..84), field(int(1, 87..88), "a", 89..90)),
+        // i = 1[0]
+        bind(
+            pattern("i", 91..92),
+            index(int(1, 95..96), int(0, 97..98), 99),
+        ),
+        // j = unknown.a
+        bind(
+            pattern("j", 100..101),
+            field(reference("unknown", 104..111), "a", 112..113),
+        ),
+    ]);
+    assert_eq!(
+        errors,
+        vec![
+            Error::FieldNotFound { span: 25..26 },
+            Error::FieldNotFound { span: 33..36 },
+            Error::IndexOutOfRange {
+                len: 2,
+                span: 46..47,
+            },
+            Error::IndexOutOfRange {
+                len: 2,
+                span: 57..59,
+            },
+            Error::TypeMismatch {
+                expected: "integer",
+                span: 69..72,
+            },
+            Error::TypeMismatch {
+                expected: "string",
+                span: 80..81,
+            },
+            Error::TypeMismatch {
+                expected: "table",
+                span: 87..88,
+            },
+            Error::TypeMismatch {
+                expected: "array or table",
+                span: 95..96,
+            },
+            // Errors in the table are reported only once.
+            Error::KeyNotFound { span: 104..111 },
+        ]
+    );
+}
+
+#[test]
+fn access_local() {
+    let (ir, errors) = interpret(&[
+        // [a]
+        header(pattern("a", 1..2)),
+        // _p = 1
+        bind(pattern("_p", 4..6), int(1, 9..10)),
+        // [a.b]
+        header(dotted(vec![key("a", 12..13), key("b", 14..15)])),
+        // q = .a._p
+        bind(pattern("q", 17..18), field(root("a", 21..23), "_p", 24..26)),
+        // [c]
+        header(pattern("c", 28..29)),
+        // r = .a._p
+        bind(pattern("r", 31..32), field(root("a", 35..37), "_p", 38..40)),
+    ]);
+    // Local keys are accessible only in tables enclosing the current scope.
+    assert_eq!(
+        errors,
+        vec![Error::InaccessibleKey {
+            found: 38..40,
+            defined: 4..6,
+        }]
+    );
+    assert_eq!(value(&ir, &["a", "b", "q"]), &literal(Literal::Integer(1)));
+}
+
+#[test]
+fn dotted_keys() {
+    let (ir, errors) = interpret(&[
+        // a.b.c = 1
+        bind(
+            dotted(vec![key("a", 0..1), key("b", 2..3), key("c", 4..5)]),
+            int(1, 8..9),
+        ),
+        // a.b.d = 2
+        bind(
+            dotted(vec![key("a", 10..11), key("b", 12..13), key("d", 14..15)]),
+            int(2, 18..19),
+        ),
+        // [x.y]
+        header(dotted(vec![key("x", 21..22), key("y", 23..24)])),
+        // z = 3
+        bind(pattern("z", 26..27), int(3, 30..31)),
+        // [x]
+        header(pattern("x", 33..34)),
+        // w = 4
+        bind(pattern("w", 36..37), int(4, 40..41)),
+    ]);
+    assert_eq!(errors, Vec::new());
+    assert_eq!(value(&ir, &["a", "b", "c"]), &literal(Literal::Integer(1)));
+    assert_eq!(value(&ir, &["a", "b", "d"]), &literal(Literal::Integer(2)));
+    assert_eq!(value(&ir, &["x", "y", "z"]), &literal(Literal::Integer(3)));
+    // The implicitly created table is extended by the later header.
+    assert_eq!(value(&ir, &["x", "w"]), &literal(Literal::Integer(4)));
+    assert_eq!(ir.root.global["x"].defined, 33..34);
+}
+
+#[test]
+fn dotted_key_errors() {
+    let (_, errors) = interpret(&[
+        // a = 1
+        bind(pattern("a", 0..1), int(1, 4..5)),
+        // a.b = 2
+        bind(dotted(vec![key("a", 6..7), key("b", 8..9)]), int(2, 12..13)),
+        // c.d = 3
+        bind(
+            dotted(vec![key("c", 14..15), key("d", 16..17)]),
+            int(3, 20..21),
+        ),
+        // c.d = 4
+        bind(
+            dotted(vec![key("c", 22..23), key("d", 24..25)]),
+            int(4, 28..29),
+        ),
+        // [c]
+        header(pattern("c", 31..32)),
+        // [t]
+        header(pattern("t", 35..36)),
+        // [t]
+        header(pattern("t", 39..40)),
+    ]);
+    assert_eq!(
+        errors,
+        vec![
+            Error::DuplicateKey {
+                found: 6..7,
+                existing: Some(0..1),
+            },
+            Error::DuplicateKey {
+                found: 24..25,
+                existing: Some(16..17),
+            },
+            // Tables created by dotted keys can't be extended by headers.
+            Error::DuplicateKey {
+                found: 31..32,
+                existing: Some(14..15),
+            },
+            Error::DuplicateKey {
+                found: 39..40,
+                existing: Some(35..36),
+            },
+        ]
+    );
+}
+
+#[test]
+fn root_keys() {
+    let (ir, errors) = interpret(&[
+        // a = 1
+        bind(pattern("a", 0..1), int(1, 4..5)),
+        // [t]
+        header(pattern("t", 7..8)),
+        // .b = 2
+        bind(root_pattern(vec![key("b", 11..12)]), int(2, 15..16)),
+        // .c.d = 3
+        bind(
+            root_pattern(vec![key("c", 18..19), key("d", 20..21)]),
+            int(3, 24..25),
+        ),
+        // .a = 4
+        bind(root_pattern(vec![key("a", 27..28)]), int(4, 31..32)),
+        // e = .b
+        bind(pattern("e", 33..34), root("b", 37..39)),
+    ]);
+    assert_eq!(
+        errors,
+        vec![Error::DuplicateKey {
+            found: 27..28,
+            existing: Some(0..1),
+        }]
+    );
+    assert_eq!(value(&ir, &["a"]), &literal(Literal::Integer(1)));
+    assert_eq!(value(&ir, &["b"]), &literal(Literal::Integer(2)));
+    assert_eq!(value(&ir, &["c", "d"]), &literal(Literal::Integer(3)));
+    assert_eq!(value(&ir, &["t", "e"]), &literal(Literal::Integer(2)));
+    assert!(!ir.root.global.contains_key("e"));
+}
+
+#[test]
+fn array_destruction() {
+    let (ir, errors) = interpret(&[
+        // [a, b] = [1, 2]
+        bind(
+            array_pattern(vec![pattern("a", 1..2), pattern("b", 4..5)], None, 0..6),
+            array(vec![int(1, 10..11), int(2, 13..14)], 9..15),
+        ),
+        // [c, ..d] = [3, 4, 5]
+        bind(
+            array_pattern(
+                vec![pattern("c", 17..18)],
+                Some(pattern("d", 22..23)),
+                16..24,
+            ),
+            array(vec![int(3, 28..29), int(4, 31..32), int(5, 34..35)], 27..36),
+        ),
+        // [[e], f] = [[6], 7]
+        bind(
+            array_pattern(
+                vec![
+                    array_pattern(vec![pattern("e", 39..40)], None, 38..41),
+                    pattern("f", 43..44),
+                ],
+                None,
+                37..45,
+            ),
+            array(
+                vec![array(vec![int(6, 50..51)], 49..52), int(7, 54..55)],
+                48..56,
+            ),
+        ),
+        // [..g] = []
+        bind(
+            array_pattern(vec![], Some(pattern("g", 60..61)), 57..62),
+            array(vec![], 65..67),
+        ),
+    ]);
+    assert_eq!(errors, Vec::new());
+    assert_eq!(value(&ir, &["a"]), &literal(Literal::Integer(1)));
+    assert_eq!(value(&ir, &["b"]), &literal(Literal::Integer(2)));
+    assert_eq!(value(&ir, &["c"]), &literal(Literal::Integer(3)));
+    assert_eq!(
+        literals(value(&ir, &["d"])),
+        [&Literal::Integer(4), &Literal::Integer(5)]
+    );
+    assert_eq!(value(&ir, &["e"]), &literal(Literal::Integer(6)));
+    assert_eq!(value(&ir, &["f"]), &literal(Literal::Integer(7)));
+    assert_eq!(literals(value(&ir, &["g"])), Vec::<&Literal>::new());
+}
+
+#[test]
+fn array_destruction_errors() {
+    let (_, errors) = interpret(&[
+        // [a, b] = [1]
+        bind(
+            array_pattern(vec![pattern("a", 1..2), pattern("b", 4..5)], None, 0..6),
+            array(vec![int(1, 10..11)], 9..12),
+        ),
+        // [c, ..d] = []
+        bind(
+            array_pattern(
+                vec![pattern("c", 14..15)],
+                Some(pattern("d", 19..20)),
+                13..21,
+            ),
+            array(vec![], 24..26),
+        ),
+        // [e] = 1
+        bind(
+            array_pattern(vec![pattern("e", 28..29)], None, 27..30),
+            int(1, 33..34),
+        ),
+        // [f, f] = [1, 2]
+        bind(
+            array_pattern(
+                vec![pattern("f", 36..37), pattern("f", 39..40)],
+                None,
+                35..41,
+            ),
+            array(vec![int(1, 45..46), int(2, 48..49)], 44..50),
+        ),
+        // [[g]]
+        header(array_pattern(vec![pattern("g", 53..54)], None, 52..55)),
+    ]);
+    assert_eq!(
+        errors,
+        vec![
+            Error::LengthMismatch {
+                expected: 2,
+                rest: false,
+                found: 1,
+                pattern: 0..6,
+                span: 9..12,
+            },
+            Error::LengthMismatch {
+                expected: 1,
+                rest: true,
+                found: 0,
+                pattern: 13..21,
+                span: 24..26,
+            },
+            Error::PatternMismatch {
+                expected: "array",
+                pattern: 27..30,
+                span: 33..34,
+            },
+            Error::DuplicateKey {
+                found: 39..40,
+                existing: Some(36..37),
+            },
+            Error::NotSupported {
+                feature: "destructions in table headers",
+                span: 52..55,
+            },
+        ]
+    );
+}
+
+#[test]
+fn table_destruction() {
+    let (ir, errors) = interpret(&[
+        // {host, port = p} = {host = 1, port = 2, user = 3}
+        bind(
+            table_pattern(
+                vec![
+                    key_pattern(key("host", 1..5), None, None),
+                    key_pattern(key("port", 7..11), Some(pattern("p", 14..15)), None),
+                ],
+                0..16,
+            ),
+            table(
+                vec![
+                    (key("host", 20..24), int(1, 27..28)),
+                    (key("port", 30..34), int(2, 37..38)),
+                    (key("user", 40..44), int(3, 47..48)),
+                ],
+                19..49,
+            ),
+        ),
+        // {timeout ? 30, retry ? unknown} = {retry = 4}
+        bind(
+            table_pattern(
+                vec![
+                    key_pattern(key("timeout", 51..58), None, Some(int(30, 61..63))),
+                    key_pattern(
+                        key("retry", 65..70),
+                        None,
+                        Some(reference("unknown", 73..80)),
+                    ),
+                ],
+                50..81,
+            ),
+            table(vec![(key("retry", 85..90), int(4, 93..94))], 84..95),
+        ),
+    ]);
+    // The default value is not evaluated if the key exists.
+    assert_eq!(errors, Vec::new());
+    assert_eq!(value(&ir, &["host"]), &literal(Literal::Integer(1)));
+    assert_eq!(value(&ir, &["p"]), &literal(Literal::Integer(2)));
+    assert_eq!(value(&ir, &["timeout"]), &literal(Literal::Integer(30)));
+    assert_eq!(value(&ir, &["retry"]), &literal(Literal::Integer(4)));
+    assert!(!ir.root.global.contains_key("port"));
+    assert!(!ir.root.global.contains_key("user"));
+}
+
+#[test]
+fn table_destruction_errors() {
+    let (_, errors) = interpret(&[
+        // {a} = {b = 1}
+        bind(
+            table_pattern(vec![key_pattern(key("a", 1..2), None, None)], 0..3),
+            table(vec![(key("b", 7..8), int(1, 11..12))], 6..13),
+        ),
+        // {c} = 1
+        bind(
+            table_pattern(vec![key_pattern(key("c", 15..16), None, None)], 14..17),
+            int(1, 20..21),
+        ),
+        // {_d} = {_d = 1}
+        bind(
+            table_pattern(vec![key_pattern(key("_d", 23..25), None, None)], 22..26),
+            table(vec![(key("_d", 30..32), int(1, 35..36))], 29..37),
+        ),
+    ]);
+    assert_eq!(
+        errors,
+        vec![
+            Error::MissingKey {
+                found: 1..2,
+                span: 6..13,
+            },
+            Error::PatternMismatch {
+                expected: "table",
+                pattern: 14..17,
+                span: 20..21,
+            },
+            Error::InaccessibleKey {
+                found: 23..25,
+                defined: 35..36,
+            },
+        ]
+    );
+}
+
+#[test]
+fn expression_functions() {
+    use BinaryOpKind::*;
+    let (ir, errors) = interpret(&[
+        // fn _sub(a, b) = a - b
+        function(
+            key("_sub", 3..7),
+            vec![key("a", 8..9), key("b", 11..12)],
+            binary(Sub, reference("a", 16..17), reference("b", 20..21)),
+        ),
+        // c = _sub(5, 3)
+        bind(
+            pattern("c", 22..23),
+            call(
+                reference("_sub", 26..30),
+                vec![int(5, 31..32), int(3, 34..35)],
+                vec![],
+                36,
+            ),
+        ),
+        // d = _sub(b = 1, a = 4)
+        bind(
+            pattern("d", 37..38),
+            call(
+                reference("_sub", 41..45),
+                vec![],
+                vec![
+                    (key("b", 46..47), int(1, 50..51)),
+                    (key("a", 53..54), int(4, 57..58)),
+                ],
+                59,
+            ),
+        ),
+        // e = _sub(7, b = 2)
+        bind(
+            pattern("e", 60..61),
+            call(
+                reference("_sub", 64..68),
+                vec![int(7, 69..70)],
+                vec![(key("b", 72..73), int(2, 76..77))],
+                78,
+            ),
+        ),
+        // _base = 10
+        bind(pattern("_base", 79..84), int(10, 87..89)),
+        // fn _port(n) = _base + n
+        function(
+            key("_port", 93..98),
+            vec![key("n", 99..100)],
+            binary(Add, reference("_base", 104..109), reference("n", 112..113)),
+        ),
+        // f = _port(1)
+        bind(
+            pattern("f", 114..115),
+            call(
+                reference("_port", 118..123),
+                vec![int(1, 124..125)],
+                vec![],
+                126,
+            ),
+        ),
+    ]);
+    assert_eq!(errors, Vec::new());
+    assert_eq!(value(&ir, &["c"]), &literal(Literal::Integer(2)));
+    assert_eq!(value(&ir, &["d"]), &literal(Literal::Integer(3)));
+    assert_eq!(value(&ir, &["e"]), &literal(Literal::Integer(5)));
+    assert_eq!(value(&ir, &["f"]), &literal(Literal::Integer(11)));
+}
+
+#[test]
+fn expression_function_errors() {
+    let (_, errors) = interpret(&[
+        // fn _f(a, b) = a
+        function(
+            key("_f", 3..5),
+            vec![key("a", 6..7), key("b", 9..10)],
+            reference("a", 14..15),
+        ),
+        // c = _f(1, 2, 3)
+        bind(
+            pattern("c", 16..17),
+            call(
+                reference("_f", 20..22),
+                vec![int(1, 23..24), int(2, 26..27), int(3, 29..30)],
+                vec![],
+                31,
+            ),
+        ),
+        // d = _f(1)
+        bind(
+            pattern("d", 32..33),
+            call(reference("_f", 36..38), vec![int(1, 39..40)], vec![], 41),
+        ),
+        // e = _f(1, 2, x = 3)
+        bind(
+            pattern("e", 42..43),
+            call(
+                reference("_f", 46..48),
+                vec![int(1, 49..50), int(2, 52..53)],
+                vec![(key("x", 55..56), int(3, 59..60))],
+                61,
+            ),
+        ),
+        // g = _f(1, a = 2, b = 3)
+        bind(
+            pattern("g", 62..63),
+            call(
+                reference("_f", 66..68),
+                vec![int(1, 69..70)],
+                vec![
+                    (key("a", 72..73), int(2, 76..77)),
+                    (key("b", 79..80), int(3, 83..84)),
+                ],
+                85,
+            ),
+        ),
+        // fn _h(a, a) = a
+        function(
+            key("_h", 89..91),
+            vec![key("a", 92..93), key("a", 95..96)],
+            reference("a", 100..101),
+        ),
+    ]);
+    assert_eq!(
+        errors,
+        vec![
+            Error::ArityMismatch {
+                expected: 2,
+                found: 3,
+                span: 20..31,
+                defined: 3..5,
+            },
+            Error::MissingArgument {
+                parameter: 9..10,
+                span: 36..41,
+            },
+            Error::UnknownArgument {
+                span: 55..56,
+                defined: 3..5,
+            },
+            Error::DuplicateArgument {
+                found: 72..73,
+                existing: 69..70,
+            },
+            Error::DuplicateKey {
+                found: 95..96,
+                existing: Some(92..93),
+            },
+        ]
+    );
+}
+
+#[test]
+fn recursion_limit() {
+    use BinaryOpKind::*;
+    // Runs on the default stack of test threads.
+    let (_, errors) = interpret(&[
+        // fn _r(n) = { a = [(_r(n) + 1) * 2] }
+        function(
+            key("_r", 3..5),
+            vec![key("n", 6..7)],
+            table(
+                vec![(
+                    key("a", 13..14),
+                    array(
+                        vec![binary(
+                            Mul,
+                            binary(
+                                Add,
+                                call(
+                                    reference("_r", 19..21),
+                                    vec![reference("n", 22..23)],
+                                    vec![],
+                                    24,
+                                ),
+                                int(1, 27..28),
+                            ),
+                            int(2, 32..33),
+                        )],
+                        17..34,
+                    ),
+                )],
+                11..36,
+            ),
+        ),
+        // b = _r(1)
+        bind(
+            pattern("b", 37..38),
+            call(reference("_r", 41..43), vec![int(1, 44..45)], vec![], 46),
+        ),
+    ]);
+    assert_eq!(
+        errors,
+        vec![Error::RecursionLimit {
+            limit: 32,
+            span: 19..24,
+        }]
+    );
+}
+
+#[test]
+fn table_functions() {
+    use BinaryOpKind::*;
+    let (ir, errors) = interpret(&[
+        // _registry = 1
+        bind(pattern("_registry", 0..9), int(1, 12..13)),
+        // [fn _web(name, port)]
+        table_function(
+            key("_web", 18..22),
+            vec![key("name", 23..27), key("port", 29..33)],
+        ),
+        // image = _registry + name
+        bind(
+            pattern("image", 35..40),
+            binary(
+                Add,
+                reference("_registry", 43..52),
+                reference("name", 55..59),
+            ),
+        ),
+        // ports = [port]
+        bind(
+            pattern("ports", 60..65),
+            array(vec![reference("port", 69..73)], 68..74),
+        ),
+        // first = ports[0]
+        bind(
+            pattern("first", 75..80),
+            index(reference("ports", 83..88), int(0, 89..90), 91),
+        ),
+        // [service = _web(10, 20)]
+        default_header(
+            TableHeaderKind::Normal,
+            pattern("service", 93..100),
+            call(
+                reference("_web", 103..107),
+                vec![int(10, 108..110), int(20, 112..114)],
+                vec![],
+                115,
+            ),
+        ),
+        // replicas = 3
+        bind(pattern("replicas", 117..125), int(3, 128..129)),
+        // [[servers = _web(30, 40)]]
+        default_header(
+            TableHeaderKind::Array,
+            pattern("servers", 132..139),
+            call(
+                reference("_web", 142..146),
+                vec![int(30, 147..149), int(40, 151..153)],
+                vec![],
+                154,
+            ),
+        ),
+        // [[servers]]
+        Statement {
+            span: 158..165,
+            kind: StatementKind::TableHeader(
+                TableHeaderKind::Array,
+                pattern("servers", 158..165),
+                None,
+            ),
+        },
+        // replicas = 2
+        bind(pattern("replicas", 168..176), int(2, 179..180)),
+    ]);
+    assert_eq!(errors, Vec::new());
+
+    let (table, errors) = export(&ir);
+    assert_eq!(errors, Vec::new());
+    assert_eq!(
+        exported(&table, &["service", "image"]),
+        &literal(Literal::Integer(11))
+    );
+    assert_eq!(
+        literals(exported(&table, &["service", "ports"])),
+        [&Literal::Integer(20)]
+    );
+    assert_eq!(
+        exported(&table, &["service", "first"]),
+        &literal(Literal::Integer(20))
+    );
+    assert_eq!(
+        exported(&table, &["service", "replicas"]),
+        &literal(Literal::Integer(3))
+    );
+
+    // The initial table is used for every element.
+    let ExprKind::Array(ref servers) = table.global["servers"].kind else {
+        panic!("`servers` is not an array");
+    };
+    let servers: Vec<_> = servers
+        .iter()
+        .map(|server| match server.kind {
+            ExprKind::Table(ref table) => (
+                exported(table, &["image"]),
+                table.global.get("replicas").map(|expr| &expr.kind),
+            ),
+            ref kind => panic!("{kind:?} is not a table"),
+        })
+        .collect();
+    assert_eq!(
+        servers,
+        [
+            (&literal(Literal::Integer(31)), None),
+            (
+                &literal(Literal::Integer(31)),
+                Some(&literal(Literal::Integer(2)))
+            ),
+        ]
+    );
+}
+
+#[test]
+fn table_function_errors() {
+    let (_, errors) = interpret(&[
+        // [fn _f(a)]
+        table_function(key("_f", 4..6), vec![key("a", 7..8)]),
+        // .b = a
+        bind(root_pattern(vec![key("b", 11..12)]), reference("a", 15..16)),
+        // fn _g(x) = x
+        function(
+            key("_g", 20..22),
+            vec![key("x", 23..24)],
+            reference("x", 28..29),
+        ),
+        // [c = _f(1)]
+        default_header(
+            TableHeaderKind::Normal,
+            pattern("c", 31..32),
+            call(reference("_f", 35..37), vec![int(1, 38..39)], vec![], 40),
+        ),
+        // [d = _f(1, 2)]
+        default_header(
+            TableHeaderKind::Normal,
+            pattern("d", 43..44),
+            call(
+                reference("_f", 47..49),
+                vec![int(1, 50..51), int(2, 53..54)],
+                vec![],
+                55,
+            ),
+        ),
+    ]);
+    assert_eq!(
+        errors,
+        vec![
+            Error::NotSupported {
+                feature: "function definitions in table functions",
+                span: 17..29,
+            },
+            Error::NotSupported {
+                feature: "root key patterns in functions",
+                span: 11..12,
+            },
+            Error::ArityMismatch {
+                expected: 1,
+                found: 2,
+                span: 47..55,
+                defined: 4..6,
+            },
+        ]
+    );
+}
+
+#[test]
+fn inline_functions() {
+    use BinaryOpKind::*;
+    let (ir, errors) = interpret(&[
+        // _ports = [1, 2, 3]
+        bind(
+            pattern("_ports", 0..6),
+            array(vec![int(1, 10..11), int(2, 13..14), int(3, 16..17)], 9..18),
+        ),
+        // a = @map(_ports, |x| x * 2)
+        bind(
+            pattern("a", 19..20),
+            call(
+                builtin("map", 23..27),
+                vec![
+                    reference("_ports", 28..34),
+                    inline(
+                        vec![key("x", 37..38)],
+                        binary(Mul, reference("x", 40..41), int(2, 44..45)),
+                        36,
+                    ),
+                ],
+                vec![],
+                46,
+            ),
+        ),
+        // b = @filter(_ports, |x| x > 1)
+        bind(
+            pattern("b", 47..48),
+            call(
+                builtin("filter", 51..58),
+                vec![
+                    reference("_ports", 59..65),
+                    inline(
+                        vec![key("x", 68..69)],
+                        binary(Gt, reference("x", 71..72), int(1, 75..76)),
+                        67,
+                    ),
+                ],
+                vec![],
+                77,
+            ),
+        ),
+        // fn _mul(n) = |x| x * n
+        function(
+            key("_mul", 81..85),
+            vec![key("n", 86..87)],
+            inline(
+                vec![key("x", 92..93)],
+                binary(Mul, reference("x", 95..96), reference("n", 99..100)),
+                91,
+            ),
+        ),
+        // c = _mul(3)(2)
+        bind(
+            pattern("c", 101..102),
+            call(
+                call(
+                    reference("_mul", 105..109),
+                    vec![int(3, 110..111)],
+                    vec![],
+                    112,
+                ),
+                vec![int(2, 113..114)],
+                vec![],
+                115,
+            ),
+        ),
+        // d = @map(_ports, _mul(10))
+        bind(
+            pattern("d", 116..117),
+            call(
+                builtin("map", 120..124),
+                vec![
+                    reference("_ports", 125..131),
+                    call(
+                        reference("_mul", 133..137),
+                        vec![int(10, 138..140)],
+                        vec![],
+                        141,
+                    ),
+                ],
+                vec![],
+                142,
+            ),
+        ),
+    ]);
+    assert_eq!(errors, Vec::new());
+    assert_eq!(
+        literals(value(&ir, &["a"])),
+        [
+            &Literal::Integer(2),
+            &Literal::Integer(4),
+            &Literal::Integer(6)
+        ]
+    );
+    assert_eq!(
+        literals(value(&ir, &["b"])),
+        [&Literal::Integer(2), &Literal::Integer(3)]
+    );
+    // Arguments of outer functions are captured.
+    assert_eq!(value(&ir, &["c"]), &literal(Literal::Integer(6)));
+    assert_eq!(
+        literals(value(&ir, &["d"])),
+        [
+            &Literal::Integer(10),
+            &Literal::Integer(20),
+            &Literal::Integer(30)
+        ]
+    );
+}
+
+#[test]
+fn inline_function_errors() {
+    let (ir, errors) = interpret(&[
+        // _a = @filter([1], |x| x)
+        bind(
+            pattern("_a", 0..2),
+            call(
+                builtin("filter", 5..12),
+                vec![
+                    array(vec![int(1, 14..15)], 13..16),
+                    inline(vec![key("x", 19..20)], reference("x", 22..23), 18),
+                ],
+                vec![],
+                24,
+            ),
+        ),
+        // _b = 1(2)
+        bind(
+            pattern("_b", 25..27),
+            call(int(1, 30..31), vec![int(2, 32..33)], vec![], 34),
+        ),
+        // _c = |x, x| x
+        bind(
+            pattern("_c", 35..37),
+            inline(
+                vec![key("x", 41..42), key("x", 44..45)],
+                reference("x", 47..48),
+                40,
+            ),
+        ),
+        // d = |x| x
+        bind(
+            pattern("d", 49..50),
+            inline(vec![key("x", 54..55)], reference("x", 57..58), 53),
+        ),
+        // e = [|x| x]
+        bind(
+            pattern("e", 59..60),
+            array(
+                vec![inline(vec![key("x", 65..66)], reference("x", 68..69), 64)],
+                63..70,
+            ),
+        ),
+    ]);
+    assert_eq!(
+        errors,
+        vec![
+            Error::TypeMismatch {
+                expected: "boolean",
+                span: 18..23,
+            },
+            Error::TypeMismatch {
+                expected: "function",
+                span: 30..31,
+            },
+            Error::DuplicateKey {
+                found: 44..45,
+                existing: Some(41..42),
+            },
+        ]
+    );
+
+    // Functions are reported when exported, in an unspecified order of keys.
+    let (_, mut errors) = export(&ir);
+    errors.sort_by_key(|err| err.span().map(|span| span.start));
+    assert_eq!(
+        errors,
+        vec![
+            Error::FunctionNotRenderable { span: 49..50 },
+            Error::FunctionNotRenderable { span: 64..69 },
+        ]
+    );
+}
@@ -11,6 +11,8 @@ pub mod symbol;
 mod utils;
 
 use drake_types::token::Token;
+use somen::error::Expects;
+use somen::parser;
 use somen::prelude::*;
 
 use identifier::identifier;
@@ -33,3 +35,39 @@ where
     ))
     .expect("token")
 }
+
+/// A parser for an invalid sequence of characters, skipped to recover from errors
+///
+/// It consumes a whole quoted string or character, or a run of characters until a whitespace, a
+/// newline, a comment, a quote, a delimiting symbol, or an operator.
+pub fn unknown<'a, I>() -> impl Parser<I, Output = ()> + 'a
+where
+    I: Input<Ok = char> + 'a,
+{
+    const DELIMITERS: &str = " \t\n\r#\"'=,[]{}()+-*/%!?<>&|";
+    choice((
+        quoted('"'),
+        quoted('\''),
+        none_of(DELIMITERS).repeat(1..).discard(),
+        any().discard(),
+    ))
+    .expect("unknown token")
+}
+
+fn quoted<'a, I>(quote: char) -> impl Parser<I, Output = ()> + 'a
+where
+    I: Input<Ok = char> + 'a,
+{
+    parser::token(quote)
+        .prefix(
+            // A backslash at the end of file escapes nothing.
+            choice((
+                parser::token('\\').prefix(any().opt()).discard(),
+                none_of([quote, '\\']).discard(),
+            ))
+            .expect(Expects::from_iter(["character"]))
+            .repeat(..)
+            .discard(),
+        )
+        .skip(parser::token(quote).opt())
+}
@@ -15,7 +15,7 @@ where
         tag("0b").prefix(fold_digits(digits_trailing_zeros(2), 0, 2, false).try_map(
             |(res, _, overflowed)| {
                 if overflowed {
-                    Err("not too large number")
+                    Err("integer up to 2^64-1")
                 } else {
                     Ok((res, Radix::Binary))
                 }
@@ -24,7 +24,7 @@ where
         tag("0o").prefix(fold_digits(digits_trailing_zeros(8), 0, 8, false).try_map(
             |(res, _, overflowed)| {
                 if overflowed {
-                    Err("not too large number")
+                    Err("integer up to 2^64-1")
                 } else {
                     Ok((res, Radix::Octal))
                 }
@@ -33,7 +33,7 @@ where
         tag("0x").prefix(
             fold_digits(digits_trailing_zeros(16), 0, 16, false).try_map(|(res, _, overflowed)| {
                 if overflowed {
-                    Err("not too large number")
+                    Err("integer up to 2^64-1")
                 } else {
                     Ok((res, Radix::Hexadecimal))
                 }
@@ -41,7 +41,7 @@ where
         ),
         fold_digits(digits(10), 0, 10, false).try_map(|(res, _, overflowed)| {
             if overflowed {
-                Err("not too large number")
+                Err("integer up to 2^64-1")
             } else {
                 Ok((res, Radix::Decimal))
             }
@@ -11,16 +11,43 @@ where
     I: Input<Ok = char> + 'a,
 {
     choice((
-        token('=').map(|_| Symbol::Assign),
-        token(',').map(|_| Symbol::Comma),
-        token('.').map(|_| Symbol::Dot),
-        token('\\').map(|_| Symbol::BackSlash),
-        token('_').map(|_| Symbol::Underscore),
-        token('@').map(|_| Symbol::At),
-        token('[').map(|_| Symbol::OpenBracket),
-        token(']').map(|_| Symbol::CloseBracket),
-        token('{').map(|_| Symbol::OpenBrace),
-        token('}').map(|_| Symbol::CloseBrace),
+        choice((
+            tag("==").map(|_| Symbol::Equal),
+            tag("!=").map(|_| Symbol::NotEqual),
+            tag("<=").map(|_| Symbol::LessEqual),
+            tag(">=").map(|_| Symbol::GreaterEqual),
+            tag("&&").map(|_| Symbol::And),
+            tag("||").map(|_| Symbol::Or),
+            tag("..").map(|_| Symbol::DoubleDot),
+        )),
+        choice((
+            token('=').map(|_| Symbol::Assign),
+            token(',').map(|_| Symbol::Comma),
+            token('.').map(|_| Symbol::Dot),
+            token('\\').map(|_| Symbol::BackSlash),
+            token('_').map(|_| Symbol::Underscore),
+            token('@').map(|_| Symbol::At),
+            token('?').map(|_| Symbol::Question),
+            token('|').map(|_| Symbol::VerticalLine),
+        )),
+        choice((
+            token('[').map(|_| Symbol::OpenBracket),
+            token(']').map(|_| Symbol::CloseBracket),
+            token('{').map(|_| Symbol::OpenBrace),
+            token('}').map(|_| Symbol::CloseBrace),
+            token('(').map(|_| Symbol::OpenParen),
+            token(')').map(|_| Symbol::CloseParen),
+        )),
+        choice((
+            token('+').map(|_| Symbol::Plus),
+            token('-').map(|_| Symbol::Minus),
+            token('*').map(|_| Symbol::Asterisk),
+            token('/').map(|_| Symbol::Slash),
+            token('%').map(|_| Symbol::Percent),
+            token('!').map(|_| Symbol::Exclamation),
+            token('<').map(|_| Symbol::LessThan),
+            token('>').map(|_| Symbol::GreaterThan),
+        )),
     ))
     .expect("symbol")
 }
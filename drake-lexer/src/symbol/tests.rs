@@ -18,6 +18,26 @@ fn symbol() {
         assert_parser(parser, "]", Symbol::CloseBracket).await;
         assert_parser(parser, "{", Symbol::OpenBrace).await;
         assert_parser(parser, "}", Symbol::CloseBrace).await;
-        assert_parser_fail(parser, "(").await;
+        assert_parser(parser, "(", Symbol::OpenParen).await;
+        assert_parser(parser, ")", Symbol::CloseParen).await;
+        assert_parser(parser, "+", Symbol::Plus).await;
+        assert_parser(parser, "-", Symbol::Minus).await;
+        assert_parser(parser, "*", Symbol::Asterisk).await;
+        assert_parser(parser, "/", Symbol::Slash).await;
+        assert_parser(parser, "%", Symbol::Percent).await;
+        assert_parser(parser, "!", Symbol::Exclamation).await;
+        assert_parser(parser, "?", Symbol::Question).await;
+        assert_parser(parser, "|", Symbol::VerticalLine).await;
+        assert_parser(parser, "<", Symbol::LessThan).await;
+        assert_parser(parser, ">", Symbol::GreaterThan).await;
+        assert_parser(parser, "==", Symbol::Equal).await;
+        assert_parser(parser, "!=", Symbol::NotEqual).await;
+        assert_parser(parser, "<=", Symbol::LessEqual).await;
+        assert_parser(parser, ">=", Symbol::GreaterEqual).await;
+        assert_parser(parser, "&&", Symbol::And).await;
+        assert_parser(parser, "||", Symbol::Or).await;
+        assert_parser(parser, "..", Symbol::DoubleDot).await;
+        assert_parser_fail(parser, "&").await;
+        assert_parser_fail(parser, "$").await;
     })
 }
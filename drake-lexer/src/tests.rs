@@ -3,7 +3,7 @@ use drake_types::token::{Identifier, IdentifierKind, Literal, Radix, Symbol, Tok
 use futures_executor::block_on;
 use somen::prelude::*;
 
-use crate::utils::assert_parser;
+use crate::utils::{assert_parser, assert_parser_fail};
 
 #[test]
 fn token() {
@@ -30,3 +30,18 @@ fn token() {
         .await;
     })
 }
+
+#[test]
+fn unknown() {
+    block_on(async {
+        let parser = &mut super::unknown().complete();
+        assert_parser(parser, "$", ()).await;
+        assert_parser(parser, "$abc", ()).await;
+        assert_parser(parser, "\"a\\qb\"", ()).await;
+        assert_parser(parser, "'ab'", ()).await;
+        assert_parser(parser, "\"abc\ndef", ()).await;
+        assert_parser_fail(parser, "$ abc").await;
+        assert_parser_fail(parser, "$=").await;
+        assert_parser_fail(parser, "").await;
+    })
+}
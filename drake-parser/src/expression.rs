@@ -2,34 +2,402 @@
 #[cfg(test)]
 mod tests;
 
+use alloc::boxed::Box;
+use alloc::string::String;
 use alloc::vec::Vec;
-use drake_types::ast::{Expression, ExpressionKind, Key};
+use drake_types::ast::{BinaryOpKind, Expression, ExpressionKind, Key, Literal, UnaryOpKind};
 use drake_types::token::{Symbol, Token};
+use somen::parser::lazy;
 use somen::{call, prelude::*};
 
 use crate::key::key;
-use crate::token::{literal, spaces, symbol};
+use crate::token::{keyword, literal, spaces, symbol};
+
+/// Pairs of keys and expressions in inline tables or named arguments
+type Pairs<L> = Vec<(Key<L>, Expression<L>)>;
+
+/// Positional and named arguments of function calls
+type Arguments<L> = (Vec<Expression<L>>, Pairs<L>);
 
 /// A parser for expression
 pub fn expression<'a, I>() -> impl Parser<I, Output = Expression<I::Locator>> + 'a
 where
     I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
+{
+    disjunction(false)
+}
+
+/// A parser for expressions inside brackets, which can contain newlines
+pub(crate) fn expression_in_bracket<'a, I>() -> impl Parser<I, Output = Expression<I::Locator>> + 'a
+where
+    I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
+{
+    disjunction(true)
+}
+
+/// A parser for logical disjunctions like `a || b`
+fn disjunction<'a, I>(in_bracket: bool) -> impl Parser<I, Output = Expression<I::Locator>> + 'a
+where
+    I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
+{
+    binary(
+        move || conjunction(in_bracket),
+        symbol(Symbol::Or).map(|_| BinaryOpKind::Or),
+        true,
+        in_bracket,
+    )
+}
+
+/// A parser for logical conjunctions like `a && b`
+fn conjunction<'a, I>(in_bracket: bool) -> impl Parser<I, Output = Expression<I::Locator>> + 'a
+where
+    I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
+{
+    binary(
+        move || comparison(in_bracket),
+        symbol(Symbol::And).map(|_| BinaryOpKind::And),
+        true,
+        in_bracket,
+    )
+}
+
+/// A parser for comparisons like `a == b`, which can't be chained
+fn comparison<'a, I>(in_bracket: bool) -> impl Parser<I, Output = Expression<I::Locator>> + 'a
+where
+    I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
+{
+    binary(
+        move || additive(in_bracket),
+        choice((
+            symbol(Symbol::Equal).map(|_| BinaryOpKind::Eq),
+            symbol(Symbol::NotEqual).map(|_| BinaryOpKind::Ne),
+            symbol(Symbol::LessThan).map(|_| BinaryOpKind::Lt),
+            symbol(Symbol::LessEqual).map(|_| BinaryOpKind::Le),
+            symbol(Symbol::GreaterThan).map(|_| BinaryOpKind::Gt),
+            symbol(Symbol::GreaterEqual).map(|_| BinaryOpKind::Ge),
+        )),
+        false,
+        in_bracket,
+    )
+}
+
+/// A parser for additive operations like `a + b`
+fn additive<'a, I>(in_bracket: bool) -> impl Parser<I, Output = Expression<I::Locator>> + 'a
+where
+    I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
+{
+    binary(
+        move || multiplicative(in_bracket),
+        choice((
+            symbol(Symbol::Plus).map(|_| BinaryOpKind::Add),
+            symbol(Symbol::Minus).map(|_| BinaryOpKind::Sub),
+        )),
+        true,
+        in_bracket,
+    )
+}
+
+/// A parser for multiplicative operations like `a * b`
+fn multiplicative<'a, I>(in_bracket: bool) -> impl Parser<I, Output = Expression<I::Locator>> + 'a
+where
+    I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
+{
+    binary(
+        move || unary(in_bracket),
+        choice((
+            symbol(Symbol::Asterisk).map(|_| BinaryOpKind::Mul),
+            symbol(Symbol::Slash).map(|_| BinaryOpKind::Div),
+            symbol(Symbol::Percent).map(|_| BinaryOpKind::Rem),
+        )),
+        true,
+        in_bracket,
+    )
+}
+
+/// A parser for left-associative binary operations with operands of higher precedence
+///
+/// Operators can't be chained like `a < b < c` if `chainable` is false.
+fn binary<'a, I, F, P, Q>(
+    operand: F,
+    operator: Q,
+    chainable: bool,
+    in_bracket: bool,
+) -> impl Parser<I, Output = Expression<I::Locator>> + 'a
+where
+    I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
+    F: Fn() -> P,
+    P: Parser<I, Output = Expression<I::Locator>> + 'a,
+    Q: Parser<I, Output = BinaryOpKind> + 'a,
+{
+    // Operators are spanned to rewind spaces before them if no operators follow.
+    operand()
+        .and(
+            spaces(in_bracket)
+                .prefix(operator)
+                .spanned()
+                .and(spaces(in_bracket).prefix(operand()))
+                .repeat(0..=if chainable { usize::MAX } else { 1 })
+                .collect::<Vec<_>>(),
+        )
+        .map(|(lhs, rest)| {
+            rest.into_iter().fold(lhs, |lhs, (kind, rhs)| Expression {
+                span: lhs.span.start.clone()..rhs.span.end.clone(),
+                kind: ExpressionKind::BinaryOp(kind, Box::new(lhs), Box::new(rhs)),
+            })
+        })
+        // Boxing to avoid exponential growth of types by nesting levels of precedence.
+        .no_state()
+        .boxed()
+}
+
+/// A parser for unary operations like `-a` or `!a`
+fn unary<'a, I>(in_bracket: bool) -> impl Parser<I, Output = Expression<I::Locator>> + 'a
+where
+    I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
+{
+    choice((
+        symbol(Symbol::Minus).map(|_| UnaryOpKind::Neg),
+        symbol(Symbol::Plus).map(|_| UnaryOpKind::Pos),
+        symbol(Symbol::Exclamation).map(|_| UnaryOpKind::Not),
+    ))
+    .with_position()
+    .skip(spaces(in_bracket))
+    .repeat(..)
+    .collect::<Vec<_>>()
+    .and(access(in_bracket))
+    .map(|(ops, expr): (Vec<_>, Expression<I::Locator>)| {
+        ops.into_iter()
+            .rev()
+            .fold(expr, |expr, (kind, span)| Expression {
+                span: span.start..expr.span.end.clone(),
+                kind: signed(kind, expr),
+            })
+    })
+}
+
+/// Applies a sign to a numeric literal, or makes an unary operation.
+///
+/// Integers less than `-2^63` are left as operations so that they will be
+/// reported as overflows.
+fn signed<L>(kind: UnaryOpKind, expr: Expression<L>) -> ExpressionKind<L> {
+    match (kind, expr.kind) {
+        (UnaryOpKind::Neg, ExpressionKind::Literal(Literal::Integer(0))) => {
+            ExpressionKind::Literal(Literal::Integer(0))
+        }
+        (UnaryOpKind::Neg, ExpressionKind::Literal(Literal::Integer(i)))
+            if i <= i64::MIN.unsigned_abs() =>
+        {
+            ExpressionKind::Literal(Literal::NegativeInteger((i as i64).wrapping_neg()))
+        }
+        (UnaryOpKind::Neg, ExpressionKind::Literal(Literal::NegativeInteger(i))) => {
+            ExpressionKind::Literal(Literal::Integer(i.unsigned_abs()))
+        }
+        (UnaryOpKind::Neg, ExpressionKind::Literal(Literal::Float(f))) => {
+            ExpressionKind::Literal(Literal::Float(-f))
+        }
+        (
+            UnaryOpKind::Pos,
+            kind @ ExpressionKind::Literal(
+                Literal::Integer(_) | Literal::NegativeInteger(_) | Literal::Float(_),
+            ),
+        ) => kind,
+        (kind, expr_kind) => ExpressionKind::UnaryOp(
+            kind,
+            Box::new(Expression {
+                kind: expr_kind,
+                span: expr.span,
+            }),
+        ),
+    }
+}
+
+/// A parser for accesses to values like `table.key` or `array[index]`
+fn access<'a, I>(in_bracket: bool) -> impl Parser<I, Output = Expression<I::Locator>> + 'a
+where
+    I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
+{
+    primary(in_bracket)
+        .and(
+            choice((
+                symbol(Symbol::Dot).prefix(key()).map(Access::Field),
+                call!(expression_in_bracket)
+                    .between(
+                        symbol(Symbol::OpenBracket).skip(spaces(true)),
+                        spaces(true).skip(symbol(Symbol::CloseBracket)),
+                    )
+                    .map(Access::Index),
+                arguments().map(|(args, named)| Access::Call(args, named)),
+            ))
+            .with_position()
+            .repeat(..)
+            .collect::<Vec<_>>(),
+        )
+        .map(|(expr, accesses)| {
+            accesses
+                .into_iter()
+                .fold(expr, |expr: Expression<I::Locator>, (access, span)| {
+                    Expression {
+                        span: expr.span.start.clone()..span.end,
+                        kind: match access {
+                            Access::Field(key) => ExpressionKind::Field(Box::new(expr), key),
+                            Access::Index(index) => {
+                                ExpressionKind::Index(Box::new(expr), Box::new(index))
+                            }
+                            Access::Call(args, named) => {
+                                ExpressionKind::Call(Box::new(expr), args, named)
+                            }
+                        },
+                    }
+                })
+        })
+        .no_state()
+        .boxed()
+}
+
+enum Access<L> {
+    Field(Key<L>),
+    Index(Expression<L>),
+    Call(Vec<Expression<L>>, Pairs<L>),
+}
+
+/// A parser for arguments of function calls like `(a, key = b)`
+///
+/// Positional arguments must precede named ones.
+fn arguments<'a, I>() -> impl Parser<I, Output = Arguments<I::Locator>> + 'a
+where
+    I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
 {
     choice((
-        literal().map(ExpressionKind::Literal),
-        array().map(ExpressionKind::Array),
-        inline_table().map(ExpressionKind::InlineTable),
+        key()
+            .skip((spaces(true), symbol(Symbol::Assign)))
+            .spanned()
+            .skip(spaces(true))
+            .and(call!(expression_in_bracket))
+            .map(|(key, expr)| (Some(key), expr)),
+        call!(expression_in_bracket).map(|expr| (None, expr)),
+    ))
+    .skip(spaces(true))
+    .sep_by_end(symbol(Symbol::Comma).skip(spaces(true)), ..)
+    .collect::<Vec<_>>()
+    .between(
+        symbol(Symbol::OpenParen).skip(spaces(true)),
+        symbol(Symbol::CloseParen),
+    )
+    .try_map(|list| {
+        let mut args = Vec::new();
+        let mut named = Vec::new();
+        for (key, expr) in list {
+            match key {
+                Some(key) => named.push((key, expr)),
+                None if named.is_empty() => args.push(expr),
+                None => return Err("a named argument"),
+            }
+        }
+        Ok((args, named))
+    })
+    .no_state()
+    .boxed()
+}
+
+/// A parser for primary expressions
+fn primary<'a, I>(in_bracket: bool) -> impl Parser<I, Output = Expression<I::Locator>> + 'a
+where
+    I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
+{
+    choice((
+        choice((
+            literal().map(ExpressionKind::Literal),
+            array().map(ExpressionKind::Array),
+            inline_table().map(ExpressionKind::InlineTable),
+        )),
+        import().map(ExpressionKind::Import),
+        key().map(ExpressionKind::Key),
+        symbol(Symbol::Dot)
+            .skip(spaces(in_bracket))
+            .prefix(key())
+            .map(ExpressionKind::Root),
+        symbol(Symbol::At)
+            .prefix(key())
+            .map(ExpressionKind::Builtin),
+        call!(expression_in_bracket)
+            .between(
+                symbol(Symbol::OpenParen).skip(spaces(true)),
+                spaces(true).skip(symbol(Symbol::CloseParen)),
+            )
+            .map(|expr| expr.kind),
+        inline_function(in_bracket)
+            .map(|(params, expr)| ExpressionKind::InlineFunction(params, Box::new(expr))),
     ))
     .with_position()
     .map(|(kind, span)| Expression { kind, span })
 }
 
+/// A parser for imports like `import("file.drake")`
+fn import<'a, I>() -> impl Parser<I, Output = String> + 'a
+where
+    I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
+{
+    // Keys named `import` can't be distinguished from imports until the parenthesis.
+    keyword("import")
+        .skip(symbol(Symbol::OpenParen))
+        .spanned()
+        .skip(spaces(true))
+        .prefix(literal().try_map(|lit| match lit {
+            Literal::String(path) => Ok(path),
+            _ => Err("a string"),
+        }))
+        .skip((spaces(true), symbol(Symbol::CloseParen)))
+        .no_state()
+        .boxed()
+}
+
+/// A parser for inline functions like `|a, b| expr` or `|| expr`
+///
+/// The body continues as far as possible, like `|x| (x + 1)`.
+fn inline_function<'a, I>(
+    in_bracket: bool,
+) -> impl Parser<I, Output = (Vec<Key<I::Locator>>, Expression<I::Locator>)> + 'a
+where
+    I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
+{
+    choice((
+        symbol(Symbol::Or).map(|_| Vec::new()),
+        key()
+            .skip(spaces(true))
+            .sep_by_end(symbol(Symbol::Comma).skip(spaces(true)), ..)
+            .collect::<Vec<_>>()
+            .between(
+                symbol(Symbol::VerticalLine).skip(spaces(true)),
+                symbol(Symbol::VerticalLine),
+            ),
+    ))
+    .skip(spaces(in_bracket))
+    .and(lazy(move || disjunction(in_bracket).no_state().boxed()))
+    .no_state()
+    .boxed()
+}
+
 /// A parser for arrays
 pub fn array<'a, I>() -> impl Parser<I, Output = Vec<Expression<I::Locator>>> + 'a
 where
     I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
 {
-    call!(expression)
+    call!(expression_in_bracket)
         .skip(spaces(true))
         .sep_by_end(symbol(Symbol::Comma).skip(spaces(true)), ..)
         .between(
@@ -40,14 +408,14 @@ where
 }
 
 /// A parser for inline tables
-pub fn inline_table<'a, I>(
-) -> impl Parser<I, Output = Vec<(Key<I::Locator>, Expression<I::Locator>)>> + 'a
+pub fn inline_table<'a, I>() -> impl Parser<I, Output = Pairs<I::Locator>> + 'a
 where
     I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
 {
     key()
         .skip((spaces(true), symbol(Symbol::Assign), spaces(true)))
-        .and(call!(expression))
+        .and(call!(expression_in_bracket))
         .skip(spaces(true))
         .sep_by_end(symbol(Symbol::Comma).skip(spaces(true)), ..)
         .between(
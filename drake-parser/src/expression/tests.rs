@@ -1,7 +1,12 @@
+use alloc::boxed::Box;
 use alloc::string::String;
 use alloc::vec;
-use drake_types::ast::{Expression, ExpressionKind, Key, KeyKind, Literal};
-use drake_types::token::{Identifier, IdentifierKind, Literal as TokenLit, Symbol, Token};
+use drake_types::ast::{
+    BinaryOpKind, Expression, ExpressionKind, Key, KeyKind, Literal, UnaryOpKind,
+};
+use drake_types::token::{
+    Identifier, IdentifierKind, Literal as TokenLit, Radix, StringKind, Symbol, Token,
+};
 use somen::prelude::*;
 
 use crate::test_utils::test_parser;
@@ -38,7 +43,557 @@ fn expression() {
                     span: 0..2,
                 }),
             ),
+            (
+                &[Token::Identifier(Identifier {
+                    kind: IdentifierKind::Bare,
+                    name: String::from("key"),
+                })],
+                Some(Expression {
+                    kind: ExpressionKind::Key(Key {
+                        kind: KeyKind::Normal,
+                        name: String::from("key"),
+                        span: 0..1,
+                    }),
+                    span: 0..1,
+                }),
+            ),
+            (
+                &[
+                    Token::Symbol(Symbol::Dot),
+                    Token::Whitespaces,
+                    Token::Symbol(Symbol::Underscore),
+                    Token::Identifier(Identifier {
+                        kind: IdentifierKind::Bare,
+                        name: String::from("key"),
+                    }),
+                ],
+                Some(Expression {
+                    kind: ExpressionKind::Root(Key {
+                        kind: KeyKind::Local,
+                        name: String::from("key"),
+                        span: 2..4,
+                    }),
+                    span: 0..4,
+                }),
+            ),
+            (
+                &[
+                    Token::Symbol(Symbol::At),
+                    Token::Identifier(Identifier {
+                        kind: IdentifierKind::Bare,
+                        name: String::from("true"),
+                    }),
+                ],
+                Some(Expression {
+                    kind: ExpressionKind::Builtin(Key {
+                        kind: KeyKind::Normal,
+                        name: String::from("true"),
+                        span: 1..2,
+                    }),
+                    span: 0..2,
+                }),
+            ),
             (&[Token::Whitespaces], None),
+            (&[Token::Symbol(Symbol::Dot)], None),
+        ],
+    );
+}
+
+#[test]
+fn operators() {
+    let int = |i, span| Expression {
+        kind: ExpressionKind::Literal(Literal::Integer(i)),
+        span,
+    };
+    let key = |name, span: core::ops::Range<usize>| Expression {
+        kind: ExpressionKind::Key(Key {
+            kind: KeyKind::Normal,
+            name: String::from(name),
+            span: span.clone(),
+        }),
+        span,
+    };
+    let binary = |kind, lhs, rhs, span| Expression {
+        kind: ExpressionKind::BinaryOp(kind, Box::new(lhs), Box::new(rhs)),
+        span,
+    };
+
+    test_parser(
+        super::expression().complete(),
+        &[
+            (
+                // 1 + 2 * 3 - 4
+                &[
+                    Token::Literal(TokenLit::Integer(1, Radix::Decimal)),
+                    Token::Whitespaces,
+                    Token::Symbol(Symbol::Plus),
+                    Token::Whitespaces,
+                    Token::Literal(TokenLit::Integer(2, Radix::Decimal)),
+                    Token::Symbol(Symbol::Asterisk),
+                    Token::Literal(TokenLit::Integer(3, Radix::Decimal)),
+                    Token::Symbol(Symbol::Minus),
+                    Token::Literal(TokenLit::Integer(4, Radix::Decimal)),
+                ],
+                Some(binary(
+                    BinaryOpKind::Sub,
+                    binary(
+                        BinaryOpKind::Add,
+                        int(1, 0..1),
+                        binary(BinaryOpKind::Mul, int(2, 4..5), int(3, 6..7), 4..7),
+                        0..7,
+                    ),
+                    int(4, 8..9),
+                    0..9,
+                )),
+            ),
+            (
+                // -(1 % 2)
+                &[
+                    Token::Symbol(Symbol::Minus),
+                    Token::Symbol(Symbol::OpenParen),
+                    Token::Literal(TokenLit::Integer(1, Radix::Decimal)),
+                    Token::Symbol(Symbol::Percent),
+                    Token::Whitespaces,
+                    Token::Symbol(Symbol::BackSlash),
+                    Token::Newline,
+                    Token::Literal(TokenLit::Integer(2, Radix::Decimal)),
+                    Token::Symbol(Symbol::CloseParen),
+                ],
+                Some(Expression {
+                    kind: ExpressionKind::UnaryOp(
+                        UnaryOpKind::Neg,
+                        Box::new(Expression {
+                            kind: binary(BinaryOpKind::Rem, int(1, 2..3), int(2, 7..8), 2..8).kind,
+                            span: 1..9,
+                        }),
+                    ),
+                    span: 0..9,
+                }),
+            ),
+            (
+                // !a || 1 + 2 == 3 && b
+                &[
+                    Token::Symbol(Symbol::Exclamation),
+                    Token::Identifier(Identifier {
+                        kind: IdentifierKind::Bare,
+                        name: String::from("a"),
+                    }),
+                    Token::Symbol(Symbol::Or),
+                    Token::Literal(TokenLit::Integer(1, Radix::Decimal)),
+                    Token::Symbol(Symbol::Plus),
+                    Token::Literal(TokenLit::Integer(2, Radix::Decimal)),
+                    Token::Symbol(Symbol::Equal),
+                    Token::Literal(TokenLit::Integer(3, Radix::Decimal)),
+                    Token::Symbol(Symbol::And),
+                    Token::Identifier(Identifier {
+                        kind: IdentifierKind::Bare,
+                        name: String::from("b"),
+                    }),
+                ],
+                Some(binary(
+                    BinaryOpKind::Or,
+                    Expression {
+                        kind: ExpressionKind::UnaryOp(UnaryOpKind::Not, Box::new(key("a", 1..2))),
+                        span: 0..2,
+                    },
+                    binary(
+                        BinaryOpKind::And,
+                        binary(
+                            BinaryOpKind::Eq,
+                            binary(BinaryOpKind::Add, int(1, 3..4), int(2, 5..6), 3..6),
+                            int(3, 7..8),
+                            3..8,
+                        ),
+                        key("b", 9..10),
+                        3..10,
+                    ),
+                    0..10,
+                )),
+            ),
+            (
+                // 1 < 2 < 3
+                &[
+                    Token::Literal(TokenLit::Integer(1, Radix::Decimal)),
+                    Token::Symbol(Symbol::LessThan),
+                    Token::Literal(TokenLit::Integer(2, Radix::Decimal)),
+                    Token::Symbol(Symbol::LessThan),
+                    Token::Literal(TokenLit::Integer(3, Radix::Decimal)),
+                ],
+                None,
+            ),
+            (
+                &[
+                    Token::Literal(TokenLit::Integer(1, Radix::Decimal)),
+                    Token::Symbol(Symbol::Plus),
+                ],
+                None,
+            ),
+            (
+                &[
+                    Token::Literal(TokenLit::Integer(1, Radix::Decimal)),
+                    Token::Newline,
+                    Token::Symbol(Symbol::Plus),
+                    Token::Literal(TokenLit::Integer(2, Radix::Decimal)),
+                ],
+                None,
+            ),
+            (
+                // (1
+                //  + 2)
+                &[
+                    Token::Symbol(Symbol::OpenParen),
+                    Token::Literal(TokenLit::Integer(1, Radix::Decimal)),
+                    Token::Newline,
+                    Token::Symbol(Symbol::Plus),
+                    Token::Literal(TokenLit::Integer(2, Radix::Decimal)),
+                    Token::Symbol(Symbol::CloseParen),
+                ],
+                Some(binary(BinaryOpKind::Add, int(1, 1..2), int(2, 4..5), 0..6)),
+            ),
+        ],
+    );
+}
+
+#[test]
+fn access() {
+    let ident = |name| {
+        Token::Identifier(Identifier {
+            kind: IdentifierKind::Bare,
+            name: String::from(name),
+        })
+    };
+    let key = |name, span| Key {
+        kind: KeyKind::Normal,
+        name: String::from(name),
+        span,
+    };
+
+    test_parser(
+        super::expression().complete(),
+        &[
+            (
+                // a.b[0]
+                &[
+                    ident("a"),
+                    Token::Symbol(Symbol::Dot),
+                    ident("b"),
+                    Token::Symbol(Symbol::OpenBracket),
+                    Token::Literal(TokenLit::Integer(0, Radix::Decimal)),
+                    Token::Symbol(Symbol::CloseBracket),
+                ],
+                Some(Expression {
+                    kind: ExpressionKind::Index(
+                        Box::new(Expression {
+                            kind: ExpressionKind::Field(
+                                Box::new(Expression {
+                                    kind: ExpressionKind::Key(key("a", 0..1)),
+                                    span: 0..1,
+                                }),
+                                key("b", 2..3),
+                            ),
+                            span: 0..3,
+                        }),
+                        Box::new(Expression {
+                            kind: ExpressionKind::Literal(Literal::Integer(0)),
+                            span: 4..5,
+                        }),
+                    ),
+                    span: 0..6,
+                }),
+            ),
+            (
+                // -a.b
+                &[
+                    Token::Symbol(Symbol::Minus),
+                    ident("a"),
+                    Token::Symbol(Symbol::Dot),
+                    ident("b"),
+                ],
+                Some(Expression {
+                    kind: ExpressionKind::UnaryOp(
+                        UnaryOpKind::Neg,
+                        Box::new(Expression {
+                            kind: ExpressionKind::Field(
+                                Box::new(Expression {
+                                    kind: ExpressionKind::Key(key("a", 1..2)),
+                                    span: 1..2,
+                                }),
+                                key("b", 3..4),
+                            ),
+                            span: 1..4,
+                        }),
+                    ),
+                    span: 0..4,
+                }),
+            ),
+            (&[ident("a"), Token::Symbol(Symbol::Dot)], None),
+            (
+                &[
+                    ident("a"),
+                    Token::Whitespaces,
+                    Token::Symbol(Symbol::Dot),
+                    ident("b"),
+                ],
+                None,
+            ),
+        ],
+    );
+}
+
+#[test]
+fn call() {
+    let ident = |name| {
+        Token::Identifier(Identifier {
+            kind: IdentifierKind::Bare,
+            name: String::from(name),
+        })
+    };
+    let key = |name, span| Key {
+        kind: KeyKind::Normal,
+        name: String::from(name),
+        span,
+    };
+    let int = |i, span| Expression {
+        kind: ExpressionKind::Literal(Literal::Integer(i)),
+        span,
+    };
+
+    test_parser(
+        super::expression().complete(),
+        &[
+            (
+                // f(1, b = 2)
+                &[
+                    ident("f"),
+                    Token::Symbol(Symbol::OpenParen),
+                    Token::Literal(TokenLit::Integer(1, Radix::Decimal)),
+                    Token::Symbol(Symbol::Comma),
+                    Token::Whitespaces,
+                    ident("b"),
+                    Token::Whitespaces,
+                    Token::Symbol(Symbol::Assign),
+                    Token::Whitespaces,
+                    Token::Literal(TokenLit::Integer(2, Radix::Decimal)),
+                    Token::Symbol(Symbol::CloseParen),
+                ],
+                Some(Expression {
+                    kind: ExpressionKind::Call(
+                        Box::new(Expression {
+                            kind: ExpressionKind::Key(key("f", 0..1)),
+                            span: 0..1,
+                        }),
+                        vec![int(1, 2..3)],
+                        vec![(key("b", 5..6), int(2, 9..10))],
+                    ),
+                    span: 0..11,
+                }),
+            ),
+            (
+                // f()
+                &[
+                    ident("f"),
+                    Token::Symbol(Symbol::OpenParen),
+                    Token::Symbol(Symbol::CloseParen),
+                ],
+                Some(Expression {
+                    kind: ExpressionKind::Call(
+                        Box::new(Expression {
+                            kind: ExpressionKind::Key(key("f", 0..1)),
+                            span: 0..1,
+                        }),
+                        vec![],
+                        vec![],
+                    ),
+                    span: 0..3,
+                }),
+            ),
+            (
+                // f(a = 1, 2)
+                &[
+                    ident("f"),
+                    Token::Symbol(Symbol::OpenParen),
+                    ident("a"),
+                    Token::Symbol(Symbol::Assign),
+                    Token::Literal(TokenLit::Integer(1, Radix::Decimal)),
+                    Token::Symbol(Symbol::Comma),
+                    Token::Literal(TokenLit::Integer(2, Radix::Decimal)),
+                    Token::Symbol(Symbol::CloseParen),
+                ],
+                None,
+            ),
+        ],
+    );
+}
+
+#[test]
+fn inline_function() {
+    let ident = |name| {
+        Token::Identifier(Identifier {
+            kind: IdentifierKind::Bare,
+            name: String::from(name),
+        })
+    };
+    let key = |name, span| Key {
+        kind: KeyKind::Normal,
+        name: String::from(name),
+        span,
+    };
+    let reference = |name, span: core::ops::Range<usize>| Expression {
+        kind: ExpressionKind::Key(key(name, span.clone())),
+        span,
+    };
+
+    test_parser(
+        super::expression().complete(),
+        &[
+            (
+                // |a, b| a + b
+                &[
+                    Token::Symbol(Symbol::VerticalLine),
+                    ident("a"),
+                    Token::Symbol(Symbol::Comma),
+                    Token::Whitespaces,
+                    ident("b"),
+                    Token::Symbol(Symbol::VerticalLine),
+                    Token::Whitespaces,
+                    ident("a"),
+                    Token::Whitespaces,
+                    Token::Symbol(Symbol::Plus),
+                    Token::Whitespaces,
+                    ident("b"),
+                ],
+                Some(Expression {
+                    kind: ExpressionKind::InlineFunction(
+                        vec![key("a", 1..2), key("b", 4..5)],
+                        Box::new(Expression {
+                            kind: ExpressionKind::BinaryOp(
+                                BinaryOpKind::Add,
+                                Box::new(reference("a", 7..8)),
+                                Box::new(reference("b", 11..12)),
+                            ),
+                            span: 7..12,
+                        }),
+                    ),
+                    span: 0..12,
+                }),
+            ),
+            (
+                // || a
+                &[Token::Symbol(Symbol::Or), Token::Whitespaces, ident("a")],
+                Some(Expression {
+                    kind: ExpressionKind::InlineFunction(vec![], Box::new(reference("a", 2..3))),
+                    span: 0..3,
+                }),
+            ),
+            (
+                &[
+                    Token::Symbol(Symbol::VerticalLine),
+                    ident("a"),
+                    Token::Symbol(Symbol::VerticalLine),
+                ],
+                None,
+            ),
+        ],
+    );
+}
+
+#[test]
+fn import() {
+    let import = || {
+        Token::Identifier(Identifier {
+            kind: IdentifierKind::Bare,
+            name: String::from("import"),
+        })
+    };
+    let path = || {
+        Token::Literal(TokenLit::String(
+            String::from("a.drake"),
+            StringKind::Normal,
+        ))
+    };
+
+    test_parser(
+        super::expression().complete(),
+        &[
+            (
+                // import("a.drake")
+                &[
+                    import(),
+                    Token::Symbol(Symbol::OpenParen),
+                    path(),
+                    Token::Symbol(Symbol::CloseParen),
+                ],
+                Some(Expression {
+                    kind: ExpressionKind::Import(String::from("a.drake")),
+                    span: 0..4,
+                }),
+            ),
+            (
+                // import(1)
+                &[
+                    import(),
+                    Token::Symbol(Symbol::OpenParen),
+                    Token::Literal(TokenLit::Integer(1, Radix::Decimal)),
+                    Token::Symbol(Symbol::CloseParen),
+                ],
+                None,
+            ),
+        ],
+    );
+}
+
+#[test]
+fn signed() {
+    let lit = |lit, span| Expression {
+        kind: ExpressionKind::Literal(lit),
+        span,
+    };
+
+    test_parser(
+        super::expression().complete(),
+        &[
+            (
+                &[
+                    Token::Symbol(Symbol::Minus),
+                    Token::Literal(TokenLit::Integer(42, Radix::Decimal)),
+                ],
+                Some(lit(Literal::NegativeInteger(-42), 0..2)),
+            ),
+            (
+                &[
+                    Token::Symbol(Symbol::Minus),
+                    Token::Literal(TokenLit::Integer(1 << 63, Radix::Decimal)),
+                ],
+                Some(lit(Literal::NegativeInteger(i64::MIN), 0..2)),
+            ),
+            (
+                &[
+                    Token::Symbol(Symbol::Minus),
+                    Token::Literal(TokenLit::Integer((1 << 63) + 1, Radix::Decimal)),
+                ],
+                Some(Expression {
+                    kind: ExpressionKind::UnaryOp(
+                        UnaryOpKind::Neg,
+                        Box::new(lit(Literal::Integer((1 << 63) + 1), 1..2)),
+                    ),
+                    span: 0..2,
+                }),
+            ),
+            (
+                &[
+                    Token::Symbol(Symbol::Plus),
+                    Token::Literal(TokenLit::Float(1.5)),
+                ],
+                Some(lit(Literal::Float(1.5), 0..2)),
+            ),
+            (
+                &[
+                    Token::Symbol(Symbol::Minus),
+                    Token::Symbol(Symbol::Minus),
+                    Token::Literal(TokenLit::Integer(1, Radix::Decimal)),
+                ],
+                Some(lit(Literal::Integer(1), 0..3)),
+            ),
         ],
     );
 }
@@ -2,22 +2,120 @@
 #[cfg(test)]
 mod tests;
 
-use drake_types::ast::{Pattern, PatternKind};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use drake_types::ast::{KeyPattern, Pattern, PatternKind};
 use drake_types::token::{Symbol, Token};
-use somen::prelude::*;
+use somen::{call, prelude::*};
 
+use crate::expression::expression_in_bracket;
 use crate::key::key;
-use crate::token::symbol;
+use crate::token::{spaces, symbol};
 
 /// A parser for patterns
 pub fn pattern<'a, I>() -> impl Parser<I, Output = Pattern<I::Locator>> + 'a
+where
+    I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
+{
+    choice((array_pattern(), table_pattern(), key_pattern()))
+}
+
+/// A parser for patterns except destructions
+pub fn key_pattern<'a, I>() -> impl Parser<I, Output = Pattern<I::Locator>> + 'a
 where
     I: Input<Ok = Token> + 'a,
 {
     choice((
         symbol(Symbol::At).prefix(key()).map(PatternKind::Builtin),
-        key().map(PatternKind::Key),
+        symbol(Symbol::Dot)
+            .skip(spaces(false))
+            .prefix(key().sep_by(symbol(Symbol::Dot), 1..).collect())
+            .map(PatternKind::Root),
+        key()
+            .sep_by(symbol(Symbol::Dot), 1..)
+            .collect::<Vec<_>>()
+            .map(|mut keys| match keys.len() {
+                1 => PatternKind::Key(keys.remove(0)),
+                _ => PatternKind::Dotted(keys),
+            }),
     ))
     .with_position()
     .map(|(kind, span)| Pattern { kind, span })
+    .no_state()
+    .boxed()
+}
+
+/// A parser for array destruction patterns
+pub fn array_pattern<'a, I>() -> impl Parser<I, Output = Pattern<I::Locator>> + 'a
+where
+    I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
+{
+    choice((
+        symbol(Symbol::DoubleDot)
+            .prefix(call!(pattern))
+            .map(|pat| (pat, true)),
+        call!(pattern).map(|pat| (pat, false)),
+    ))
+    .skip(spaces(true))
+    .sep_by_end(symbol(Symbol::Comma).skip(spaces(true)), ..)
+    .collect::<Vec<_>>()
+    .between(
+        symbol(Symbol::OpenBracket).skip(spaces(true)),
+        symbol(Symbol::CloseBracket),
+    )
+    .try_map(|mut pats| {
+        let rest = match pats.last() {
+            Some((_, true)) => pats.pop().map(|(pat, _)| Box::new(pat)),
+            _ => None,
+        };
+        if pats.iter().any(|(_, rest)| *rest) {
+            Err("a rest pattern at the end")
+        } else {
+            Ok(PatternKind::Array(
+                pats.into_iter().map(|(pat, _)| pat).collect(),
+                rest,
+            ))
+        }
+    })
+    .with_position()
+    .map(|(kind, span)| Pattern { kind, span })
+    .no_state()
+    .boxed()
+}
+
+/// A parser for table destruction patterns
+pub fn table_pattern<'a, I>() -> impl Parser<I, Output = Pattern<I::Locator>> + 'a
+where
+    I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
+{
+    (
+        key().skip(spaces(true)),
+        (symbol(Symbol::Assign), spaces(true))
+            .prefix(call!(pattern))
+            .skip(spaces(true))
+            .opt(),
+        (symbol(Symbol::Question), spaces(true))
+            .prefix(call!(expression_in_bracket))
+            .skip(spaces(true))
+            .opt(),
+    )
+        .map(|(key, pattern, default)| KeyPattern {
+            key,
+            pattern,
+            default,
+        })
+        .sep_by_end(symbol(Symbol::Comma).skip(spaces(true)), ..)
+        .collect()
+        .between(
+            symbol(Symbol::OpenBrace).skip(spaces(true)),
+            symbol(Symbol::CloseBrace),
+        )
+        .map(PatternKind::Table)
+        .with_position()
+        .map(|(kind, span)| Pattern { kind, span })
+        .no_state()
+        .boxed()
 }
@@ -1,6 +1,10 @@
+use alloc::boxed::Box;
 use alloc::string::String;
-use drake_types::ast::{Key, KeyKind, Pattern, PatternKind};
-use drake_types::token::{Identifier, IdentifierKind, Symbol, Token};
+use alloc::vec;
+use drake_types::ast::{
+    Expression, ExpressionKind, Key, KeyKind, KeyPattern, Literal, Pattern, PatternKind,
+};
+use drake_types::token::{Identifier, IdentifierKind, Literal as TokenLit, Symbol, Token};
 use somen::prelude::*;
 
 use crate::test_utils::test_parser;
@@ -41,7 +45,199 @@ fn pattern() {
                     span: 0..1,
                 }),
             ),
+            (
+                &[
+                    Token::Identifier(Identifier {
+                        kind: IdentifierKind::Bare,
+                        name: String::from("a"),
+                    }),
+                    Token::Symbol(Symbol::Dot),
+                    Token::Symbol(Symbol::Underscore),
+                    Token::Identifier(Identifier {
+                        kind: IdentifierKind::Bare,
+                        name: String::from("b"),
+                    }),
+                ],
+                Some(Pattern {
+                    kind: PatternKind::Dotted(vec![
+                        Key {
+                            kind: KeyKind::Normal,
+                            name: String::from("a"),
+                            span: 0..1,
+                        },
+                        Key {
+                            kind: KeyKind::Local,
+                            name: String::from("b"),
+                            span: 2..4,
+                        },
+                    ]),
+                    span: 0..4,
+                }),
+            ),
+            (
+                &[
+                    Token::Identifier(Identifier {
+                        kind: IdentifierKind::Bare,
+                        name: String::from("a"),
+                    }),
+                    Token::Symbol(Symbol::Dot),
+                ],
+                None,
+            ),
+            (
+                &[
+                    Token::Symbol(Symbol::Dot),
+                    Token::Whitespaces,
+                    Token::Identifier(Identifier {
+                        kind: IdentifierKind::Bare,
+                        name: String::from("a"),
+                    }),
+                ],
+                Some(Pattern {
+                    kind: PatternKind::Root(vec![Key {
+                        kind: KeyKind::Normal,
+                        name: String::from("a"),
+                        span: 2..3,
+                    }]),
+                    span: 0..3,
+                }),
+            ),
+            (&[Token::Symbol(Symbol::Dot)], None),
             (&[Token::Whitespaces], None),
         ],
     );
 }
+
+#[test]
+fn array_pattern() {
+    let key = |name, span: core::ops::Range<usize>| Pattern {
+        kind: PatternKind::Key(Key {
+            kind: KeyKind::Normal,
+            name: String::from(name),
+            span: span.clone(),
+        }),
+        span,
+    };
+    let ident = |name| {
+        Token::Identifier(Identifier {
+            kind: IdentifierKind::Bare,
+            name: String::from(name),
+        })
+    };
+
+    test_parser(
+        super::array_pattern().complete(),
+        &[
+            (
+                &[
+                    Token::Symbol(Symbol::OpenBracket),
+                    Token::Symbol(Symbol::CloseBracket),
+                ],
+                Some(Pattern {
+                    kind: PatternKind::Array(vec![], None),
+                    span: 0..2,
+                }),
+            ),
+            (
+                // [a, ..b]
+                &[
+                    Token::Symbol(Symbol::OpenBracket),
+                    ident("a"),
+                    Token::Symbol(Symbol::Comma),
+                    Token::Whitespaces,
+                    Token::Symbol(Symbol::DoubleDot),
+                    ident("b"),
+                    Token::Symbol(Symbol::CloseBracket),
+                ],
+                Some(Pattern {
+                    kind: PatternKind::Array(vec![key("a", 1..2)], Some(Box::new(key("b", 5..6)))),
+                    span: 0..7,
+                }),
+            ),
+            (
+                // [..a, b]
+                &[
+                    Token::Symbol(Symbol::OpenBracket),
+                    Token::Symbol(Symbol::DoubleDot),
+                    ident("a"),
+                    Token::Symbol(Symbol::Comma),
+                    ident("b"),
+                    Token::Symbol(Symbol::CloseBracket),
+                ],
+                None,
+            ),
+        ],
+    );
+}
+
+#[test]
+fn table_pattern() {
+    let key = |name, span| Key {
+        kind: KeyKind::Normal,
+        name: String::from(name),
+        span,
+    };
+    let ident = |name| {
+        Token::Identifier(Identifier {
+            kind: IdentifierKind::Bare,
+            name: String::from(name),
+        })
+    };
+
+    test_parser(
+        super::table_pattern().complete(),
+        &[
+            (
+                // { a, b = c ? 'd' }
+                &[
+                    Token::Symbol(Symbol::OpenBrace),
+                    Token::Whitespaces,
+                    ident("a"),
+                    Token::Symbol(Symbol::Comma),
+                    Token::Whitespaces,
+                    ident("b"),
+                    Token::Whitespaces,
+                    Token::Symbol(Symbol::Assign),
+                    Token::Whitespaces,
+                    ident("c"),
+                    Token::Whitespaces,
+                    Token::Symbol(Symbol::Question),
+                    Token::Whitespaces,
+                    Token::Literal(TokenLit::Character('d')),
+                    Token::Whitespaces,
+                    Token::Symbol(Symbol::CloseBrace),
+                ],
+                Some(Pattern {
+                    kind: PatternKind::Table(vec![
+                        KeyPattern {
+                            key: key("a", 2..3),
+                            pattern: None,
+                            default: None,
+                        },
+                        KeyPattern {
+                            key: key("b", 5..6),
+                            pattern: Some(Pattern {
+                                kind: PatternKind::Key(key("c", 9..10)),
+                                span: 9..10,
+                            }),
+                            default: Some(Expression {
+                                kind: ExpressionKind::Literal(Literal::Character('d')),
+                                span: 13..14,
+                            }),
+                        },
+                    ]),
+                    span: 0..16,
+                }),
+            ),
+            (
+                &[
+                    Token::Symbol(Symbol::OpenBrace),
+                    ident("a"),
+                    Token::Symbol(Symbol::Question),
+                    Token::Symbol(Symbol::CloseBrace),
+                ],
+                None,
+            ),
+        ],
+    );
+}
@@ -2,21 +2,45 @@
 #[cfg(test)]
 mod tests;
 
-use drake_types::ast::{Expression, Pattern, Statement, StatementKind, TableHeaderKind};
+use alloc::vec::Vec;
+use drake_types::ast::{Expression, Key, Pattern, Statement, StatementKind, TableHeaderKind};
 use drake_types::token::{Symbol, Token};
 use somen::prelude::*;
 
 use crate::expression::expression;
-use crate::pattern::pattern;
-use crate::token::{spaces, symbol};
+use crate::key::key;
+use crate::pattern::{array_pattern, key_pattern, pattern, table_pattern};
+use crate::token::{keyword, newline, spaces, symbol};
+
+/// A parser for lines, an optional statement followed by a newline or the end of input
+pub fn line<'a, I>() -> impl Parser<I, Output = Option<Statement<I::Locator>>> + 'a
+where
+    I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
+{
+    spaces(false)
+        .prefix(statement().opt())
+        .skip(spaces(false))
+        .skip(choice((newline(), eof())))
+}
+
+/// A key, parameters, and a body of an expression function
+type ExpressionFunction<L> = (Key<L>, Vec<Key<L>>, Expression<L>);
+
+/// A kind, a pattern, and a default value of a table header
+type TableHeader<L> = (TableHeaderKind, Pattern<L>, Option<Expression<L>>);
 
 /// A parser for statements
 pub fn statement<'a, I>() -> impl Parser<I, Output = Statement<I::Locator>> + 'a
 where
     I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
 {
     choice((
+        expression_function()
+            .map(|(key, params, expr)| StatementKind::ExpressionFunction(key, params, expr)),
         value_binding().map(|(pat, expr)| StatementKind::ValueBinding(pat, expr)),
+        table_function().map(|(key, params)| StatementKind::TableFunction(key, params)),
         table_header().map(|(kind, pat, expr)| StatementKind::TableHeader(kind, pat, expr)),
     ))
     .with_position()
@@ -28,23 +52,81 @@ pub fn value_binding<'a, I>(
 ) -> impl Parser<I, Output = (Pattern<I::Locator>, Expression<I::Locator>)> + 'a
 where
     I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
+{
+    choice((
+        // Array patterns can't be distinguished from table headers until the equals sign.
+        array_pattern()
+            .skip((spaces(false), symbol(Symbol::Assign)))
+            .spanned(),
+        table_pattern().skip((spaces(false), symbol(Symbol::Assign))),
+        key_pattern().skip((spaces(false), symbol(Symbol::Assign))),
+    ))
+    .skip(spaces(false))
+    .and(expression())
+}
+
+/// A parser for expression function definitions like `fn f(a, b) = expr`
+pub fn expression_function<'a, I>() -> impl Parser<I, Output = ExpressionFunction<I::Locator>> + 'a
+where
+    I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
 {
-    pattern()
+    // Keys named `fn` can't be distinguished from definitions until the parenthesis.
+    keyword("fn")
+        .skip(spaces(false))
+        .prefix(key())
+        .skip(symbol(Symbol::OpenParen))
+        .spanned()
+        .and(parameters())
         .skip((spaces(false), symbol(Symbol::Assign), spaces(false)))
         .and(expression())
+        .map(|((key, params), expr)| (key, params, expr))
+        .no_state()
+        .boxed()
+}
+
+/// A parser for table function headers like `[fn f(a, b)]`
+pub fn table_function<'a, I>(
+) -> impl Parser<I, Output = (Key<I::Locator>, Vec<Key<I::Locator>>)> + 'a
+where
+    I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
+{
+    // Table headers of keys named `fn` can't be distinguished until the parenthesis.
+    (
+        symbol(Symbol::OpenBracket),
+        spaces(true),
+        keyword("fn"),
+        spaces(true),
+    )
+        .prefix(key())
+        .skip(symbol(Symbol::OpenParen))
+        .spanned()
+        .and(parameters())
+        .skip((spaces(true), symbol(Symbol::CloseBracket)))
+        .no_state()
+        .boxed()
+}
+
+/// A parser for parameters of functions after the opening parenthesis, like `a, b)`
+fn parameters<'a, I>() -> impl Parser<I, Output = Vec<Key<I::Locator>>> + 'a
+where
+    I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
+{
+    key()
+        .skip(spaces(true))
+        .sep_by_end(symbol(Symbol::Comma).skip(spaces(true)), ..)
+        .collect::<Vec<_>>()
+        .between(spaces(true), symbol(Symbol::CloseParen))
 }
 
 /// A parser for table headers
-pub fn table_header<'a, I>() -> impl Parser<
-    I,
-    Output = (
-        TableHeaderKind,
-        Pattern<I::Locator>,
-        Option<Expression<I::Locator>>,
-    ),
-> + 'a
+pub fn table_header<'a, I>() -> impl Parser<I, Output = TableHeader<I::Locator>> + 'a
 where
     I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
 {
     symbol(Symbol::OpenBracket)
         .skip(spaces(true))
@@ -74,6 +156,7 @@ pub fn table_header_inner<'a, I>(
 ) -> impl Parser<I, Output = (Pattern<I::Locator>, Option<Expression<I::Locator>>)> + 'a
 where
     I: Input<Ok = Token> + 'a,
+    I::Locator: Clone,
 {
     (
         pattern(),
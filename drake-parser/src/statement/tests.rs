@@ -1,4 +1,5 @@
 use alloc::string::String;
+use alloc::vec;
 use drake_types::ast::{
     Expression, ExpressionKind, Key, KeyKind, Literal, Pattern, PatternKind, Statement,
     StatementKind, TableHeaderKind,
@@ -8,6 +9,66 @@ use somen::prelude::*;
 
 use crate::test_utils::test_parser;
 
+#[test]
+fn line() {
+    test_parser(
+        super::line().complete(),
+        &[
+            (&[], Some(None)),
+            (
+                &[
+                    Token::Whitespaces,
+                    Token::Comment(String::from("comment")),
+                    Token::Newline,
+                ],
+                Some(None),
+            ),
+            (
+                &[
+                    Token::Whitespaces,
+                    Token::Identifier(Identifier {
+                        kind: IdentifierKind::Bare,
+                        name: String::from("abc"),
+                    }),
+                    Token::Symbol(Symbol::Assign),
+                    Token::Literal(LitToken::Character('a')),
+                    Token::Comment(String::from("comment")),
+                    Token::Newline,
+                ],
+                Some(Some(Statement {
+                    kind: StatementKind::ValueBinding(
+                        Pattern {
+                            kind: PatternKind::Key(Key {
+                                kind: KeyKind::Normal,
+                                name: String::from("abc"),
+                                span: 1..2,
+                            }),
+                            span: 1..2,
+                        },
+                        Expression {
+                            kind: ExpressionKind::Literal(Literal::Character('a')),
+                            span: 3..4,
+                        },
+                    ),
+                    span: 1..4,
+                })),
+            ),
+            (
+                &[
+                    Token::Identifier(Identifier {
+                        kind: IdentifierKind::Bare,
+                        name: String::from("abc"),
+                    }),
+                    Token::Symbol(Symbol::Assign),
+                    Token::Literal(LitToken::Character('a')),
+                    Token::Literal(LitToken::Character('b')),
+                ],
+                None,
+            ),
+        ],
+    );
+}
+
 #[test]
 fn statement() {
     test_parser(
@@ -65,6 +126,41 @@ fn statement() {
                     span: 0..3,
                 }),
             ),
+            (
+                &[
+                    Token::Symbol(Symbol::OpenBracket),
+                    Token::Identifier(Identifier {
+                        kind: IdentifierKind::Bare,
+                        name: String::from("abc"),
+                    }),
+                    Token::Symbol(Symbol::CloseBracket),
+                    Token::Symbol(Symbol::Assign),
+                    Token::Literal(LitToken::Character('a')),
+                ],
+                Some(Statement {
+                    kind: StatementKind::ValueBinding(
+                        Pattern {
+                            kind: PatternKind::Array(
+                                vec![Pattern {
+                                    kind: PatternKind::Key(Key {
+                                        kind: KeyKind::Normal,
+                                        name: String::from("abc"),
+                                        span: 1..2,
+                                    }),
+                                    span: 1..2,
+                                }],
+                                None,
+                            ),
+                            span: 0..3,
+                        },
+                        Expression {
+                            kind: ExpressionKind::Literal(Literal::Character('a')),
+                            span: 4..5,
+                        },
+                    ),
+                    span: 0..5,
+                }),
+            ),
             (&[Token::Whitespaces], None),
         ],
     );
@@ -295,3 +391,169 @@ fn table_header() {
         ],
     );
 }
+
+#[test]
+fn expression_function() {
+    let ident = |name| {
+        Token::Identifier(Identifier {
+            kind: IdentifierKind::Bare,
+            name: String::from(name),
+        })
+    };
+    let key = |name, span| Key {
+        kind: KeyKind::Normal,
+        name: String::from(name),
+        span,
+    };
+
+    test_parser(
+        super::statement().complete(),
+        &[
+            (
+                // fn f(a, b) = a
+                &[
+                    ident("fn"),
+                    Token::Whitespaces,
+                    ident("f"),
+                    Token::Symbol(Symbol::OpenParen),
+                    ident("a"),
+                    Token::Symbol(Symbol::Comma),
+                    Token::Whitespaces,
+                    ident("b"),
+                    Token::Symbol(Symbol::CloseParen),
+                    Token::Whitespaces,
+                    Token::Symbol(Symbol::Assign),
+                    Token::Whitespaces,
+                    ident("a"),
+                ],
+                Some(Statement {
+                    kind: StatementKind::ExpressionFunction(
+                        key("f", 2..3),
+                        vec![key("a", 4..5), key("b", 7..8)],
+                        Expression {
+                            kind: ExpressionKind::Key(key("a", 12..13)),
+                            span: 12..13,
+                        },
+                    ),
+                    span: 0..13,
+                }),
+            ),
+            (
+                // fn = 'a'
+                &[
+                    ident("fn"),
+                    Token::Whitespaces,
+                    Token::Symbol(Symbol::Assign),
+                    Token::Whitespaces,
+                    Token::Literal(LitToken::Character('a')),
+                ],
+                Some(Statement {
+                    kind: StatementKind::ValueBinding(
+                        Pattern {
+                            kind: PatternKind::Key(key("fn", 0..1)),
+                            span: 0..1,
+                        },
+                        Expression {
+                            kind: ExpressionKind::Literal(Literal::Character('a')),
+                            span: 4..5,
+                        },
+                    ),
+                    span: 0..5,
+                }),
+            ),
+            (
+                // fn f(a)
+                &[
+                    ident("fn"),
+                    Token::Whitespaces,
+                    ident("f"),
+                    Token::Symbol(Symbol::OpenParen),
+                    ident("a"),
+                    Token::Symbol(Symbol::CloseParen),
+                ],
+                None,
+            ),
+        ],
+    );
+}
+
+#[test]
+fn table_function() {
+    let ident = |name| {
+        Token::Identifier(Identifier {
+            kind: IdentifierKind::Bare,
+            name: String::from(name),
+        })
+    };
+    let key = |name, span| Key {
+        kind: KeyKind::Normal,
+        name: String::from(name),
+        span,
+    };
+
+    test_parser(
+        super::statement().complete(),
+        &[
+            (
+                // [fn f(a, b)]
+                &[
+                    Token::Symbol(Symbol::OpenBracket),
+                    ident("fn"),
+                    Token::Whitespaces,
+                    ident("f"),
+                    Token::Symbol(Symbol::OpenParen),
+                    ident("a"),
+                    Token::Symbol(Symbol::Comma),
+                    Token::Whitespaces,
+                    ident("b"),
+                    Token::Symbol(Symbol::CloseParen),
+                    Token::Symbol(Symbol::CloseBracket),
+                ],
+                Some(Statement {
+                    kind: StatementKind::TableFunction(
+                        key("f", 3..4),
+                        vec![key("a", 5..6), key("b", 8..9)],
+                    ),
+                    span: 0..11,
+                }),
+            ),
+            (
+                // [fn]
+                &[
+                    Token::Symbol(Symbol::OpenBracket),
+                    ident("fn"),
+                    Token::Symbol(Symbol::CloseBracket),
+                ],
+                Some(Statement {
+                    kind: StatementKind::TableHeader(
+                        TableHeaderKind::Normal,
+                        Pattern {
+                            kind: PatternKind::Key(key("fn", 1..2)),
+                            span: 1..2,
+                        },
+                        None,
+                    ),
+                    span: 0..3,
+                }),
+            ),
+            (
+                // [fn f(a) = 1]
+                &[
+                    Token::Symbol(Symbol::OpenBracket),
+                    ident("fn"),
+                    Token::Whitespaces,
+                    ident("f"),
+                    Token::Symbol(Symbol::OpenParen),
+                    ident("a"),
+                    Token::Symbol(Symbol::CloseParen),
+                    Token::Whitespaces,
+                    Token::Symbol(Symbol::Assign),
+                    Token::Whitespaces,
+                    Token::Literal(LitToken::Character('a')),
+                    Token::Symbol(Symbol::CloseBracket),
+                ],
+                None,
+            ),
+        ],
+    );
+}
@@ -4,7 +4,7 @@ mod tests;
 
 use alloc::string::String;
 use drake_types::ast::Literal;
-use drake_types::token::{Literal as TokenLit, Symbol, Token};
+use drake_types::token::{Identifier, IdentifierKind, Literal as TokenLit, Symbol, Token};
 use somen::prelude::*;
 
 /// Spaces and line continuouses
@@ -66,6 +66,24 @@ where
     token(Token::Symbol(symbol)).discard()
 }
 
+/// A keyword, a bare identifier with the specified name
+pub fn keyword<'a, I>(name: &'static str) -> impl Parser<I, Output = ()> + 'a
+where
+    I: Positioned<Ok = Token> + 'a,
+{
+    is(move |token| {
+        matches!(
+            token,
+            Token::Identifier(Identifier {
+                kind: IdentifierKind::Bare,
+                name: ref ident,
+            }) if ident == name
+        )
+    })
+    .discard()
+    .expect(name)
+}
+
 /// An identifier
 pub fn identifier<'a, I>() -> impl Parser<I, Output = String> + 'a
 where
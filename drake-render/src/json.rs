@@ -0,0 +1,114 @@
+//! A renderer for JSON
+#[cfg(test)]
+mod tests;
+
+use alloc::string::{String, ToString};
+use core::fmt::Write;
+use core::ops::Range;
+use drake_types::ast::Literal;
+use drake_types::error::Error;
+use drake_types::ir::Ir;
+
+use crate::value::{root, Entries, Value, ValueKind};
+
+/// Renders an IR to a JSON text.
+pub fn render<L: Clone>(ir: &Ir<L>) -> Result<String, Error<L>> {
+    let mut buf = String::new();
+    table(&mut buf, &root(ir)?, 0)?;
+    buf.push('\n');
+    Ok(buf)
+}
+
+fn value<L: Clone>(buf: &mut String, val: &Value<'_, L>, indent: usize) -> Result<(), Error<L>> {
+    match val.kind {
+        ValueKind::Literal(lit) => literal(buf, lit, &val.span),
+        ValueKind::Array(ref arr) if arr.is_empty() => {
+            buf.push_str("[]");
+            Ok(())
+        }
+        ValueKind::Array(ref arr) => {
+            buf.push('[');
+            for (i, elem) in arr.iter().enumerate() {
+                if i != 0 {
+                    buf.push(',');
+                }
+                newline(buf, indent + 1);
+                value(buf, elem, indent + 1)?;
+            }
+            newline(buf, indent);
+            buf.push(']');
+            Ok(())
+        }
+        ValueKind::Table(ref entries) => table(buf, entries, indent),
+    }
+}
+
+fn table<L: Clone>(
+    buf: &mut String,
+    entries: &Entries<'_, L>,
+    indent: usize,
+) -> Result<(), Error<L>> {
+    if entries.is_empty() {
+        buf.push_str("{}");
+        return Ok(());
+    }
+
+    buf.push('{');
+    for (i, (key, val)) in entries.iter().enumerate() {
+        if i != 0 {
+            buf.push(',');
+        }
+        newline(buf, indent + 1);
+        string(buf, key);
+        buf.push_str(": ");
+        value(buf, val, indent + 1)?;
+    }
+    newline(buf, indent);
+    buf.push('}');
+    Ok(())
+}
+
+fn literal<L: Clone>(buf: &mut String, lit: &Literal, span: &Range<L>) -> Result<(), Error<L>> {
+    match lit {
+        Literal::Character(c) => string(buf, c.encode_utf8(&mut [0; 4])),
+        Literal::String(s) => string(buf, s),
+        Literal::Integer(i) => buf.push_str(&i.to_string()),
+        Literal::NegativeInteger(i) => buf.push_str(&i.to_string()),
+        Literal::Boolean(b) => buf.push_str(if *b { "true" } else { "false" }),
+        Literal::Null => buf.push_str("null"),
+        Literal::Float(f) if f.is_finite() => write!(buf, "{f:?}").unwrap(),
+        _ => {
+            return Err(Error::NotRenderable {
+                filetype: "json",
+                span: span.clone(),
+            })
+        }
+    }
+    Ok(())
+}
+
+/// Writes a string as a JSON string literal.
+pub fn string(buf: &mut String, s: &str) {
+    buf.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            '\n' => buf.push_str("\\n"),
+            '\r' => buf.push_str("\\r"),
+            '\t' => buf.push_str("\\t"),
+            '\u{08}' => buf.push_str("\\b"),
+            '\u{0C}' => buf.push_str("\\f"),
+            c if c.is_control() => write!(buf, "\\u{:04x}", c as u32).unwrap(),
+            c => buf.push(c),
+        }
+    }
+    buf.push('"');
+}
+
+fn newline(buf: &mut String, indent: usize) {
+    buf.push('\n');
+    for _ in 0..indent {
+        buf.push_str("  ");
+    }
+}
@@ -0,0 +1,180 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use drake_types::ast::{Expression, ExpressionKind, Literal};
+use drake_types::error::Error;
+use drake_types::ir::{Element, ElementKind, Expr, ExprKind, Function, FunctionBody, Ir, Table};
+
+fn literal(lit: Literal) -> Expr<usize> {
+    Expr {
+        kind: ExprKind::Literal(lit),
+        span: 0..0,
+    }
+}
+
+fn expr_elem(expr: Expr<usize>) -> Element<usize> {
+    Element {
+        kind: ElementKind::Expr(expr),
+        defined: 0..0,
+    }
+}
+
+#[test]
+fn render() {
+    let mut ir = Ir::new();
+    assert_eq!(super::render(&ir), Ok(String::from("{}\n")));
+
+    ir.root.global.insert(
+        String::from("key"),
+        expr_elem(literal(Literal::String(String::from("a\"b\n")))),
+    );
+    ir.root.local.insert(
+        String::from("local"),
+        expr_elem(literal(Literal::Integer(1))),
+    );
+    ir.root.global.insert(
+        String::from("array"),
+        expr_elem(Expr {
+            kind: ExprKind::Array(vec![
+                literal(Literal::Integer(1)),
+                literal(Literal::Float(2.0)),
+                literal(Literal::Character('c')),
+                literal(Literal::Boolean(true)),
+                literal(Literal::Null),
+            ]),
+            span: 0..0,
+        }),
+    );
+    assert_eq!(
+        super::render(&ir),
+        Ok(String::from(
+            "{\n  \"array\": [\n    1,\n    2.0,\n    \"c\",\n    true,\n    null\n  ],\n  \"key\": \"a\\\"b\\n\"\n}\n"
+        ))
+    );
+}
+
+#[test]
+fn header() {
+    let mut default = Table::new();
+    default
+        .global
+        .insert(String::from("foo"), literal(Literal::Integer(1)));
+    default
+        .global
+        .insert(String::from("bar"), literal(Literal::Integer(2)));
+    let default = Expr {
+        kind: ExprKind::Table(default),
+        span: 0..0,
+    };
+
+    let mut table = Table::new();
+    table
+        .global
+        .insert(String::from("bar"), expr_elem(literal(Literal::Integer(3))));
+
+    let mut ir = Ir::new();
+    ir.root.global.insert(
+        String::from("table"),
+        Element {
+            kind: ElementKind::Table(table.clone(), Some(default.clone())),
+            defined: 0..0,
+        },
+    );
+    ir.root.global.insert(
+        String::from("array"),
+        Element {
+            kind: ElementKind::Array(vec![table, Table::new()], Some(default)),
+            defined: 0..0,
+        },
+    );
+    assert_eq!(
+        super::render(&ir),
+        Ok(String::from(concat!(
+            "{\n",
+            "  \"array\": [\n",
+            "    {\n      \"bar\": 3,\n      \"foo\": 1\n    },\n",
+            "    {\n      \"bar\": 2,\n      \"foo\": 1\n    }\n",
+            "  ],\n",
+            "  \"table\": {\n    \"bar\": 3,\n    \"foo\": 1\n  }\n",
+            "}\n",
+        )))
+    );
+
+    let mut ir = Ir::new();
+    ir.root.global.insert(
+        String::from("table"),
+        Element {
+            kind: ElementKind::Table(
+                Table::new(),
+                Some(Expr {
+                    kind: ExprKind::Literal(Literal::Integer(1)),
+                    span: 3..4,
+                }),
+            ),
+            defined: 0..1,
+        },
+    );
+    assert_eq!(
+        super::render(&ir),
+        Err(Error::TypeMismatch {
+            expected: "table",
+            span: 3..4,
+        })
+    );
+}
+
+#[test]
+fn not_renderable() {
+    let mut ir = Ir::new();
+    ir.root.global.insert(
+        String::from("inf"),
+        expr_elem(Expr {
+            kind: ExprKind::Literal(Literal::Float(f64::INFINITY)),
+            span: 4..5,
+        }),
+    );
+    assert_eq!(
+        super::render(&ir),
+        Err(Error::NotRenderable {
+            filetype: "json",
+            span: 4..5,
+        })
+    );
+}
+
+#[test]
+fn function() {
+    let func = Function {
+        parameters: vec![],
+        body: FunctionBody::Expression(Expression {
+            kind: ExpressionKind::Literal(Literal::Null),
+            span: 8..9,
+        }),
+        scope: vec![],
+        captured: Table::new(),
+        defined: 3..4,
+    };
+
+    let mut ir = Ir::new();
+    ir.root.global.insert(
+        String::from("f"),
+        Element {
+            kind: ElementKind::Expr(Expr {
+                kind: ExprKind::Function(Box::new(func)),
+                span: 4..9,
+            }),
+            defined: 0..1,
+        },
+    );
+    assert_eq!(
+        super::render(&ir),
+        Err(Error::FunctionNotRenderable { span: 0..1 })
+    );
+}
+
+#[test]
+fn string() {
+    let mut buf = String::new();
+    super::string(&mut buf, "a \"b\" \\ c\n\td\u{7f}\u{8}→");
+    assert_eq!(buf, "\"a \\\"b\\\" \\\\ c\\n\\td\\u007f\\b→\"");
+}
@@ -0,0 +1,7 @@
+#![no_std]
+extern crate alloc;
+
+pub mod json;
+pub mod toml;
+mod value;
+pub mod yaml;
@@ -0,0 +1,170 @@
+//! A renderer for TOML
+#[cfg(test)]
+mod tests;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+use core::ops::Range;
+use drake_types::ast::Literal;
+use drake_types::error::Error;
+use drake_types::ir::Ir;
+
+use crate::value::{root, Entries, Value, ValueKind};
+
+/// Renders an IR to a TOML text.
+pub fn render<L: Clone>(ir: &Ir<L>) -> Result<String, Error<L>> {
+    let mut buf = String::new();
+    table(&mut buf, &mut Vec::new(), &root(ir)?)?;
+    Ok(buf)
+}
+
+/// Writes key/value pairs, followed by sub tables and arrays of tables declared by headers.
+fn table<'a, L: Clone>(
+    buf: &mut String,
+    path: &mut Vec<&'a str>,
+    entries: &Entries<'a, L>,
+) -> Result<(), Error<L>> {
+    for (key, val) in entries.iter() {
+        if !is_header(val) {
+            self::key(buf, key);
+            buf.push_str(" = ");
+            inline(buf, val, &val.defined)?;
+            buf.push('\n');
+        }
+    }
+
+    for (key, val) in entries.iter() {
+        if !is_header(val) {
+            continue;
+        }
+
+        path.push(key);
+        match val.kind {
+            ValueKind::Table(ref entries) => {
+                header(buf, path, false);
+                table(buf, path, entries)?;
+            }
+            ValueKind::Array(ref arr) => {
+                for elem in arr {
+                    header(buf, path, true);
+                    match elem.kind {
+                        ValueKind::Table(ref entries) => table(buf, path, entries)?,
+                        _ => return Err(Error::Unexpected),
+                    }
+                }
+            }
+            ValueKind::Literal(_) => return Err(Error::Unexpected),
+        }
+        path.pop();
+    }
+
+    Ok(())
+}
+
+/// Checks whether the value should be written as a table header.
+fn is_header<L>(val: &Value<'_, L>) -> bool {
+    val.header && matches!(val.kind, ValueKind::Table(_) | ValueKind::Array(_))
+}
+
+fn header(buf: &mut String, path: &[&str], array: bool) {
+    if !buf.is_empty() {
+        buf.push('\n');
+    }
+
+    buf.push_str(if array { "[[" } else { "[" });
+    for (i, key) in path.iter().enumerate() {
+        if i != 0 {
+            buf.push('.');
+        }
+        self::key(buf, key);
+    }
+    buf.push_str(if array { "]]\n" } else { "]\n" });
+}
+
+/// Writes an inline value, reporting values TOML can't represent at `defined`.
+fn inline<L: Clone>(
+    buf: &mut String,
+    val: &Value<'_, L>,
+    defined: &Range<L>,
+) -> Result<(), Error<L>> {
+    match val.kind {
+        ValueKind::Literal(lit) => literal(buf, lit, defined)?,
+        ValueKind::Array(ref arr) => {
+            buf.push('[');
+            for (i, elem) in arr.iter().enumerate() {
+                if i != 0 {
+                    buf.push_str(", ");
+                }
+                inline(buf, elem, defined)?;
+            }
+            buf.push(']');
+        }
+        ValueKind::Table(ref entries) if entries.is_empty() => buf.push_str("{}"),
+        ValueKind::Table(ref entries) => {
+            buf.push_str("{ ");
+            for (i, (key, val)) in entries.iter().enumerate() {
+                if i != 0 {
+                    buf.push_str(", ");
+                }
+                self::key(buf, key);
+                buf.push_str(" = ");
+                inline(buf, val, defined)?;
+            }
+            buf.push_str(" }");
+        }
+    }
+    Ok(())
+}
+
+fn literal<L: Clone>(buf: &mut String, lit: &Literal, defined: &Range<L>) -> Result<(), Error<L>> {
+    match lit {
+        Literal::Character(c) => string(buf, c.encode_utf8(&mut [0; 4])),
+        Literal::String(s) => string(buf, s),
+        // TOML only accepts 64 bit signed integers.
+        Literal::Integer(i) if *i <= i64::MAX as u64 => buf.push_str(&i.to_string()),
+        Literal::NegativeInteger(i) => buf.push_str(&i.to_string()),
+        Literal::Float(f) if f.is_nan() => buf.push_str("nan"),
+        Literal::Float(f) if f.is_infinite() && f.is_sign_positive() => buf.push_str("inf"),
+        Literal::Float(f) if f.is_infinite() => buf.push_str("-inf"),
+        Literal::Float(f) => write!(buf, "{f:?}").unwrap(),
+        Literal::Boolean(b) => buf.push_str(if *b { "true" } else { "false" }),
+        _ => {
+            return Err(Error::NotRenderable {
+                filetype: "toml",
+                span: defined.clone(),
+            })
+        }
+    }
+    Ok(())
+}
+
+fn key(buf: &mut String, key: &str) {
+    if !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        buf.push_str(key);
+    } else {
+        string(buf, key);
+    }
+}
+
+fn string(buf: &mut String, s: &str) {
+    buf.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            '\n' => buf.push_str("\\n"),
+            '\r' => buf.push_str("\\r"),
+            '\t' => buf.push_str("\\t"),
+            '\u{08}' => buf.push_str("\\b"),
+            '\u{0C}' => buf.push_str("\\f"),
+            c if c.is_control() => write!(buf, "\\u{:04X}", c as u32).unwrap(),
+            c => buf.push(c),
+        }
+    }
+    buf.push('"');
+}
@@ -0,0 +1,135 @@
+use alloc::string::String;
+use alloc::vec;
+use drake_types::ast::Literal;
+use drake_types::error::Error;
+use drake_types::ir::{Element, ElementKind, Expr, ExprKind, Ir, Table};
+
+fn literal(lit: Literal) -> Expr<usize> {
+    Expr {
+        kind: ExprKind::Literal(lit),
+        span: 0..0,
+    }
+}
+
+fn expr_elem(expr: Expr<usize>) -> Element<usize> {
+    Element {
+        kind: ElementKind::Expr(expr),
+        defined: 0..0,
+    }
+}
+
+#[test]
+fn render() {
+    let mut ir = Ir::new();
+    assert_eq!(super::render(&ir), Ok(String::new()));
+
+    let mut inline = Table::new();
+    inline
+        .global
+        .insert(String::from("a"), literal(Literal::Integer(1)));
+    inline.global.insert(
+        String::from("raw key"),
+        literal(Literal::String(String::from("\"b\""))),
+    );
+    ir.root.global.insert(
+        String::from("inline"),
+        expr_elem(Expr {
+            kind: ExprKind::Table(inline),
+            span: 0..0,
+        }),
+    );
+    ir.root.global.insert(
+        String::from("float"),
+        expr_elem(literal(Literal::Float(3.0))),
+    );
+
+    let mut table = Table::new();
+    table.global.insert(
+        String::from("array"),
+        expr_elem(Expr {
+            kind: ExprKind::Array(vec![
+                literal(Literal::Integer(1)),
+                literal(Literal::NegativeInteger(i64::MIN)),
+                literal(Literal::Character('c')),
+            ]),
+            span: 0..0,
+        }),
+    );
+    ir.root.global.insert(
+        String::from("table"),
+        Element {
+            kind: ElementKind::Table(table, None),
+            defined: 0..0,
+        },
+    );
+
+    let mut user = Table::new();
+    user.global.insert(
+        String::from("name"),
+        expr_elem(literal(Literal::String(String::from("Alice")))),
+    );
+    ir.root.global.insert(
+        String::from("users list"),
+        Element {
+            kind: ElementKind::Array(vec![user, Table::new()], None),
+            defined: 0..0,
+        },
+    );
+
+    assert_eq!(
+        super::render(&ir),
+        Ok(String::from(concat!(
+            "float = 3.0\n",
+            "inline = { a = 1, \"raw key\" = \"\\\"b\\\"\" }\n",
+            "\n",
+            "[table]\n",
+            "array = [1, -9223372036854775808, \"c\"]\n",
+            "\n",
+            "[[\"users list\"]]\n",
+            "name = \"Alice\"\n",
+            "\n",
+            "[[\"users list\"]]\n",
+        )))
+    );
+}
+
+#[test]
+fn not_renderable() {
+    let mut ir = Ir::new();
+    ir.root.global.insert(
+        String::from("large"),
+        Element {
+            kind: ElementKind::Expr(Expr {
+                kind: ExprKind::Literal(Literal::Integer(u64::MAX)),
+                span: 8..28,
+            }),
+            defined: 0..5,
+        },
+    );
+    assert_eq!(
+        super::render(&ir),
+        Err(Error::NotRenderable {
+            filetype: "toml",
+            span: 0..5,
+        })
+    );
+
+    let mut ir = Ir::new();
+    ir.root.global.insert(
+        String::from("null"),
+        Element {
+            kind: ElementKind::Expr(Expr {
+                kind: ExprKind::Array(vec![literal(Literal::Integer(1)), literal(Literal::Null)]),
+                span: 7..16,
+            }),
+            defined: 0..4,
+        },
+    );
+    assert_eq!(
+        super::render(&ir),
+        Err(Error::NotRenderable {
+            filetype: "toml",
+            span: 0..4,
+        })
+    );
+}
@@ -0,0 +1,145 @@
+//! Values prepared for rendering
+use alloc::vec::Vec;
+use core::ops::Range;
+use drake_types::ast::Literal;
+use drake_types::error::Error;
+use drake_types::ir::{Element, ElementKind, Expr, ExprKind, Ir, Table};
+
+/// Key/value pairs of a table, sorted by keys
+pub type Entries<'a, L> = Vec<(&'a str, Value<'a, L>)>;
+
+/// A value with local keys removed and initial tables merged
+#[derive(Clone, Debug, PartialEq)]
+pub struct Value<'a, L> {
+    /// The kind of the value
+    pub kind: ValueKind<'a, L>,
+    /// Whether the value is declared by a table header
+    pub header: bool,
+    /// The range in the file
+    pub span: Range<L>,
+    /// The range where the value is bound, which is the key of an element or the value itself
+    pub defined: Range<L>,
+}
+
+/// Kinds of values
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValueKind<'a, L> {
+    /// A literal
+    Literal(&'a Literal),
+    /// An array
+    Array(Vec<Value<'a, L>>),
+    /// A table
+    Table(Entries<'a, L>),
+}
+
+/// Collects renderable entries of the root table.
+pub fn root<L: Clone>(ir: &Ir<L>) -> Result<Entries<'_, L>, Error<L>> {
+    element_table(&ir.root, None)
+}
+
+fn element<L: Clone>(elem: &Element<L>) -> Result<Value<'_, L>, Error<L>> {
+    let kind = match elem.kind {
+        ElementKind::Table(ref table, ref default) => {
+            ValueKind::Table(element_table(table, default.as_ref())?)
+        }
+        ElementKind::Array(ref arr, ref default) => {
+            let mut tables = Vec::with_capacity(arr.len());
+            for table in arr {
+                tables.push(Value {
+                    kind: ValueKind::Table(element_table(table, default.as_ref())?),
+                    header: true,
+                    span: elem.defined.clone(),
+                    defined: elem.defined.clone(),
+                });
+            }
+            ValueKind::Array(tables)
+        }
+        // Functions are reported at the bound key.
+        ElementKind::Expr(Expr {
+            kind: ExprKind::Function(_),
+            ..
+        }) => {
+            return Err(Error::FunctionNotRenderable {
+                span: elem.defined.clone(),
+            })
+        }
+        ElementKind::Expr(ref expr) => {
+            return Ok(Value {
+                defined: elem.defined.clone(),
+                ..expression(expr)?
+            })
+        }
+    };
+
+    Ok(Value {
+        kind,
+        header: true,
+        span: elem.defined.clone(),
+        defined: elem.defined.clone(),
+    })
+}
+
+fn element_table<'a, L: Clone>(
+    table: &'a Table<Element<L>>,
+    default: Option<&'a Expr<L>>,
+) -> Result<Entries<'a, L>, Error<L>> {
+    let mut entries = Vec::new();
+
+    if let Some(default) = default {
+        match default.kind {
+            ExprKind::Table(ref def) => {
+                for (key, expr) in def.global.iter() {
+                    if !table.global.contains_key(key) {
+                        entries.push((key.as_str(), expression(expr)?));
+                    }
+                }
+            }
+            _ => {
+                return Err(Error::TypeMismatch {
+                    expected: "table",
+                    span: default.span.clone(),
+                })
+            }
+        }
+    }
+
+    for (key, elem) in table.global.iter() {
+        entries.push((key.as_str(), element(elem)?));
+    }
+
+    entries.sort_unstable_by_key(|(key, _)| *key);
+    Ok(entries)
+}
+
+fn expression<L: Clone>(expr: &Expr<L>) -> Result<Value<'_, L>, Error<L>> {
+    let kind = match expr.kind {
+        ExprKind::Literal(ref lit) => ValueKind::Literal(lit),
+        ExprKind::Array(ref arr) => {
+            let mut values = Vec::with_capacity(arr.len());
+            for elem in arr {
+                values.push(expression(elem)?);
+            }
+            ValueKind::Array(values)
+        }
+        ExprKind::Table(ref table) => {
+            let mut entries = Vec::with_capacity(table.global.len());
+            for (key, expr) in table.global.iter() {
+                entries.push((key.as_str(), expression(expr)?));
+            }
+            entries.sort_unstable_by_key(|(key, _)| *key);
+            ValueKind::Table(entries)
+        }
+        ExprKind::Function(_) => {
+            return Err(Error::FunctionNotRenderable {
+                span: expr.span.clone(),
+            })
+        }
+    };
+
+    Ok(Value {
+        kind,
+        header: false,
+        span: expr.span.clone(),
+        defined: expr.span.clone(),
+    })
+}
@@ -0,0 +1,304 @@
+//! A renderer for YAML
+#[cfg(test)]
+mod tests;
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::fmt::Write;
+use core::ops::Range;
+use drake_types::ast::Literal;
+use drake_types::error::Error;
+use drake_types::ir::Ir;
+
+use crate::value::{root, Entries, Value, ValueKind};
+
+/// Renders an IR to a YAML text.
+pub fn render<L: Clone>(ir: &Ir<L>) -> Result<String, Error<L>> {
+    let mut buf = String::new();
+    let root = root(ir)?;
+    if root.is_empty() {
+        buf.push_str("{}\n");
+    } else {
+        mapping(&mut buf, &root, 0, false)?;
+    }
+    Ok(buf)
+}
+
+/// Writes a block mapping, the first line will not be indented if `inline` is `true`.
+fn mapping<L: Clone>(
+    buf: &mut String,
+    entries: &Entries<'_, L>,
+    indent: usize,
+    inline: bool,
+) -> Result<(), Error<L>> {
+    for (i, (key, val)) in entries.iter().enumerate() {
+        if i != 0 || !inline {
+            spaces(buf, indent);
+        }
+        string(buf, key);
+        buf.push(':');
+        match val.kind {
+            ValueKind::Table(ref entries) if !entries.is_empty() => {
+                buf.push('\n');
+                mapping(buf, entries, indent + 2, false)?;
+            }
+            ValueKind::Array(ref arr) if !arr.is_empty() => {
+                buf.push('\n');
+                sequence(buf, arr, indent + 2, false)?;
+            }
+            _ => {
+                buf.push(' ');
+                flow(buf, val)?;
+                buf.push('\n');
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes a block sequence, the first line will not be indented if `inline` is `true`.
+fn sequence<L: Clone>(
+    buf: &mut String,
+    arr: &[Value<'_, L>],
+    indent: usize,
+    inline: bool,
+) -> Result<(), Error<L>> {
+    for (i, val) in arr.iter().enumerate() {
+        if i != 0 || !inline {
+            spaces(buf, indent);
+        }
+        buf.push_str("- ");
+        match val.kind {
+            ValueKind::Table(ref entries) if !entries.is_empty() => {
+                mapping(buf, entries, indent + 2, true)?;
+            }
+            ValueKind::Array(ref arr) if !arr.is_empty() => {
+                sequence(buf, arr, indent + 2, true)?;
+            }
+            _ => {
+                flow(buf, val)?;
+                buf.push('\n');
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes scalars and empty collections.
+fn flow<L: Clone>(buf: &mut String, val: &Value<'_, L>) -> Result<(), Error<L>> {
+    match val.kind {
+        ValueKind::Literal(lit) => literal(buf, lit, &val.span)?,
+        ValueKind::Array(_) => buf.push_str("[]"),
+        ValueKind::Table(_) => buf.push_str("{}"),
+    }
+    Ok(())
+}
+
+fn literal<L: Clone>(buf: &mut String, lit: &Literal, span: &Range<L>) -> Result<(), Error<L>> {
+    match lit {
+        Literal::Character(c) => string(buf, c.encode_utf8(&mut [0; 4])),
+        Literal::String(s) => string(buf, s),
+        Literal::Integer(i) => buf.push_str(&i.to_string()),
+        Literal::NegativeInteger(i) => buf.push_str(&i.to_string()),
+        Literal::Boolean(b) => buf.push_str(if *b { "true" } else { "false" }),
+        Literal::Null => buf.push_str("null"),
+        Literal::Float(f) if f.is_nan() => buf.push_str(".nan"),
+        Literal::Float(f) if f.is_infinite() && f.is_sign_positive() => buf.push_str(".inf"),
+        Literal::Float(f) if f.is_infinite() => buf.push_str("-.inf"),
+        Literal::Float(f) => float(buf, *f),
+        _ => {
+            return Err(Error::NotRenderable {
+                filetype: "yaml",
+                span: span.clone(),
+            })
+        }
+    }
+    Ok(())
+}
+
+/// Writes a finite float which YAML 1.1 readers also resolve as a float, like `1.0e+16`.
+fn float(buf: &mut String, f: f64) {
+    let repr = format!("{f:?}");
+    match repr.split_once('e') {
+        Some((mantissa, exp)) => {
+            buf.push_str(mantissa);
+            if !mantissa.contains('.') {
+                buf.push_str(".0");
+            }
+            buf.push('e');
+            if !exp.starts_with('-') {
+                buf.push('+');
+            }
+            buf.push_str(exp);
+        }
+        None => buf.push_str(&repr),
+    }
+}
+
+fn string(buf: &mut String, s: &str) {
+    if is_plain(s) {
+        buf.push_str(s);
+        return;
+    }
+
+    buf.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            '\n' => buf.push_str("\\n"),
+            '\r' => buf.push_str("\\r"),
+            '\t' => buf.push_str("\\t"),
+            c if c.is_control() => write!(buf, "\\u{:04x}", c as u32).unwrap(),
+            c => buf.push(c),
+        }
+    }
+    buf.push('"');
+}
+
+/// Checks whether the string can be written without quotes and read back as a string.
+fn is_plain(s: &str) -> bool {
+    let first = match s.chars().next() {
+        Some(c) => c,
+        None => return false,
+    };
+
+    if "-?:,[]{}#&*!|>'\"%@`".contains(first)
+        || first.is_whitespace()
+        || s.ends_with(char::is_whitespace)
+        || s.ends_with(':')
+        || s.contains(": ")
+        || s.contains(" #")
+        || s.chars().any(char::is_control)
+    {
+        return false;
+    }
+
+    !is_reserved(s) && !is_numeric(s) && !is_timestamp(s)
+}
+
+/// Checks whether the string is read as a boolean or a null.
+fn is_reserved(s: &str) -> bool {
+    matches!(
+        s,
+        "~" | "null"
+            | "Null"
+            | "NULL"
+            | "true"
+            | "True"
+            | "TRUE"
+            | "false"
+            | "False"
+            | "FALSE"
+            | "y"
+            | "Y"
+            | "yes"
+            | "Yes"
+            | "YES"
+            | "n"
+            | "N"
+            | "no"
+            | "No"
+            | "NO"
+            | "on"
+            | "On"
+            | "ON"
+            | "off"
+            | "Off"
+            | "OFF"
+    )
+}
+
+/// Checks whether the string is read as a number.
+fn is_numeric(s: &str) -> bool {
+    let s = s.strip_prefix(['+', '-']).unwrap_or(s);
+    if matches!(s, ".inf" | ".Inf" | ".INF" | ".nan" | ".NaN" | ".NAN") {
+        return true;
+    }
+
+    // Integers with radix prefixes are numbers in YAML 1.1 and 1.2.
+    if s.starts_with("0x") || s.starts_with("0o") || s.starts_with("0b") {
+        return true;
+    }
+
+    // Allowing `:` also covers sexagesimal numbers in YAML 1.1.
+    let mut digits = false;
+    for c in s.chars() {
+        match c {
+            '0'..='9' => digits = true,
+            '_' | '.' | 'e' | 'E' | '+' | '-' | ':' => {}
+            _ => return false,
+        }
+    }
+    digits
+}
+
+/// Checks whether the string is read as a timestamp in YAML 1.1.
+fn is_timestamp(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    let mut pos = 0;
+    // Consumes digits up to `max`, and returns whether at least `min` digits are consumed.
+    let digits = |pos: &mut usize, min: usize, max: usize| {
+        let start = *pos;
+        while *pos - start < max && bytes.get(*pos).is_some_and(u8::is_ascii_digit) {
+            *pos += 1;
+        }
+        *pos - start >= min
+    };
+    let byte = |pos: &mut usize, c: u8| {
+        let found = bytes.get(*pos) == Some(&c);
+        *pos += found as usize;
+        found
+    };
+    let blanks = |pos: &mut usize| {
+        let start = *pos;
+        while matches!(bytes.get(*pos), Some(b' ' | b'\t')) {
+            *pos += 1;
+        }
+        *pos > start
+    };
+
+    // The date like `2001-12-14`, which can be followed by the time.
+    if !(digits(&mut pos, 4, 4)
+        && byte(&mut pos, b'-')
+        && digits(&mut pos, 1, 2)
+        && byte(&mut pos, b'-')
+        && digits(&mut pos, 1, 2))
+    {
+        return false;
+    }
+    if pos == bytes.len() {
+        return true;
+    }
+
+    // The time like `T21:59:43.10`.
+    if !(byte(&mut pos, b'T') || byte(&mut pos, b't') || blanks(&mut pos))
+        || !(digits(&mut pos, 1, 2)
+            && byte(&mut pos, b':')
+            && digits(&mut pos, 2, 2)
+            && byte(&mut pos, b':')
+            && digits(&mut pos, 2, 2))
+    {
+        return false;
+    }
+    if byte(&mut pos, b'.') {
+        digits(&mut pos, 0, usize::MAX);
+    }
+
+    // The time zone like `Z` or `-05:00`.
+    blanks(&mut pos);
+    if byte(&mut pos, b'+') || byte(&mut pos, b'-') {
+        if !digits(&mut pos, 1, 2) || byte(&mut pos, b':') && !digits(&mut pos, 2, 2) {
+            return false;
+        }
+    } else {
+        byte(&mut pos, b'Z');
+    }
+    pos == bytes.len()
+}
+
+fn spaces(buf: &mut String, indent: usize) {
+    for _ in 0..indent {
+        buf.push(' ');
+    }
+}
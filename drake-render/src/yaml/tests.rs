@@ -0,0 +1,156 @@
+use alloc::string::String;
+use alloc::vec;
+use drake_types::ast::Literal;
+use drake_types::ir::{Element, ElementKind, Expr, ExprKind, Ir, Table};
+
+fn literal(lit: Literal) -> Expr<usize> {
+    Expr {
+        kind: ExprKind::Literal(lit),
+        span: 0..0,
+    }
+}
+
+fn expr_elem(expr: Expr<usize>) -> Element<usize> {
+    Element {
+        kind: ElementKind::Expr(expr),
+        defined: 0..0,
+    }
+}
+
+#[test]
+fn render() {
+    let mut ir = Ir::new();
+    assert_eq!(super::render(&ir), Ok(String::from("{}\n")));
+
+    let mut inner = Table::new();
+    inner
+        .global
+        .insert(String::from("a"), literal(Literal::Integer(1)));
+    inner
+        .global
+        .insert(String::from("b"), literal(Literal::Float(0.5)));
+
+    ir.root.global.insert(
+        String::from("array"),
+        expr_elem(Expr {
+            kind: ExprKind::Array(vec![
+                literal(Literal::Integer(1)),
+                Expr {
+                    kind: ExprKind::Table(inner),
+                    span: 0..0,
+                },
+                Expr {
+                    kind: ExprKind::Array(vec![
+                        literal(Literal::Character('c')),
+                        Expr {
+                            kind: ExprKind::Array(vec![]),
+                            span: 0..0,
+                        },
+                    ]),
+                    span: 0..0,
+                },
+            ]),
+            span: 0..0,
+        }),
+    );
+    ir.root.local.insert(
+        String::from("local"),
+        expr_elem(literal(Literal::Integer(1))),
+    );
+
+    let mut table = Table::new();
+    table.global.insert(
+        String::from("key"),
+        expr_elem(literal(Literal::String(String::from("value")))),
+    );
+    ir.root.global.insert(
+        String::from("table"),
+        Element {
+            kind: ElementKind::Table(table.clone(), None),
+            defined: 0..0,
+        },
+    );
+    ir.root.global.insert(
+        String::from("tables"),
+        Element {
+            kind: ElementKind::Array(vec![table, Table::new()], None),
+            defined: 0..0,
+        },
+    );
+
+    assert_eq!(
+        super::render(&ir),
+        Ok(String::from(concat!(
+            "array:\n",
+            "  - 1\n",
+            "  - a: 1\n",
+            "    b: 0.5\n",
+            "  - - c\n",
+            "    - []\n",
+            "table:\n",
+            "  key: value\n",
+            "tables:\n",
+            "  - key: value\n",
+            "  - {}\n",
+        )))
+    );
+}
+
+#[test]
+fn string() {
+    let cases = [
+        ("abc", "abc"),
+        ("hello world", "hello world"),
+        ("", "\"\""),
+        ("true", "\"true\""),
+        ("No", "\"No\""),
+        ("null", "\"null\""),
+        ("~", "\"~\""),
+        ("42", "\"42\""),
+        ("-1.5e3", "\"-1.5e3\""),
+        ("0x1F", "\"0x1F\""),
+        (".inf", "\".inf\""),
+        ("12:30", "\"12:30\""),
+        ("1.2.3a", "1.2.3a"),
+        ("2001-12-14", "\"2001-12-14\""),
+        ("1979-05-27T07:32:00Z", "\"1979-05-27T07:32:00Z\""),
+        (
+            "2001-12-14t21:59:43.10-05:00",
+            "\"2001-12-14t21:59:43.10-05:00\"",
+        ),
+        ("2001-12-14 21:59:43.10 -5", "\"2001-12-14 21:59:43.10 -5\""),
+        ("2001-12-14 noon", "2001-12-14 noon"),
+        ("1979-05-27T07:32", "1979-05-27T07:32"),
+        ("- item", "\"- item\""),
+        ("key: value", "\"key: value\""),
+        ("a #b", "\"a #b\""),
+        (" a", "\" a\""),
+        ("a\nb", "\"a\\nb\""),
+        ("\"quoted\"", "\"\\\"quoted\\\"\""),
+    ];
+
+    for (input, output) in cases {
+        let mut buf = String::new();
+        super::string(&mut buf, input);
+        assert_eq!(buf, output);
+    }
+}
+
+#[test]
+fn float() {
+    let cases = [
+        (0.5, "0.5"),
+        (3.0, "3.0"),
+        (-2.0, "-2.0"),
+        (1e16, "1.0e+16"),
+        (1.5e300, "1.5e+300"),
+        (1e-7, "1.0e-7"),
+        (-2.5e-10, "-2.5e-10"),
+    ];
+
+    for (input, output) in cases {
+        let mut buf = String::new();
+        super::float(&mut buf, input);
+        assert_eq!(buf, output);
+    }
+}
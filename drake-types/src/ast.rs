@@ -1,4 +1,5 @@
 //! Types for parsers
+use alloc::boxed::Box;
 use alloc::string::String;
 use alloc::vec::Vec;
 use core::fmt;
@@ -28,6 +29,10 @@ pub enum StatementKind<L> {
     ValueBinding(Pattern<L>, Expression<L>),
     /// A table header
     TableHeader(TableHeaderKind, Pattern<L>, Option<Expression<L>>),
+    /// An expression function definition like `fn f(a, b) = expr`
+    ExpressionFunction(Key<L>, Vec<Key<L>>, Expression<L>),
+    /// A table function header like `[fn f(a, b)]`
+    TableFunction(Key<L>, Vec<Key<L>>),
 }
 
 impl<L> PartialEq for StatementKind<L> {
@@ -39,6 +44,13 @@ impl<L> PartialEq for StatementKind<L> {
             (Self::TableHeader(kind1, pat1, def1), Self::TableHeader(kind2, pat2, def2)) => {
                 kind1 == kind2 && pat1 == pat2 && def1 == def2
             }
+            (
+                Self::ExpressionFunction(key1, params1, expr1),
+                Self::ExpressionFunction(key2, params2, expr2),
+            ) => key1 == key2 && params1 == params2 && expr1 == expr2,
+            (Self::TableFunction(key1, params1), Self::TableFunction(key2, params2)) => {
+                key1 == key2 && params1 == params2
+            }
             _ => false,
         }
     }
@@ -80,6 +92,14 @@ pub enum PatternKind<L> {
     Key(Key<L>),
     /// A built-in pattern
     Builtin(Key<L>),
+    /// A dotted key pattern like `a.b.c`
+    Dotted(Vec<Key<L>>),
+    /// A root key pattern like `.key` or `.a.b`
+    Root(Vec<Key<L>>),
+    /// An array destruction pattern like `[a, b, ..rest]`
+    Array(Vec<Pattern<L>>, Option<Box<Pattern<L>>>),
+    /// A table destruction pattern like `{ a, b = pattern ? default }`
+    Table(Vec<KeyPattern<L>>),
 }
 
 impl<L> PartialEq for PatternKind<L> {
@@ -87,6 +107,12 @@ impl<L> PartialEq for PatternKind<L> {
         match (self, other) {
             (Self::Key(key1), Self::Key(key2)) => key1 == key2,
             (Self::Builtin(key1), Self::Builtin(key2)) => key1 == key2,
+            (Self::Dotted(keys1), Self::Dotted(keys2)) => keys1 == keys2,
+            (Self::Root(keys1), Self::Root(keys2)) => keys1 == keys2,
+            (Self::Array(pats1, rest1), Self::Array(pats2, rest2)) => {
+                pats1 == pats2 && rest1 == rest2
+            }
+            (Self::Table(keys1), Self::Table(keys2)) => keys1 == keys2,
             _ => false,
         }
     }
@@ -94,6 +120,24 @@ impl<L> PartialEq for PatternKind<L> {
 
 impl<L> Eq for PatternKind<L> {}
 
+/// Keys in table destruction patterns
+#[derive(Clone, Debug)]
+pub struct KeyPattern<L> {
+    /// The key in the table
+    pub key: Key<L>,
+    /// The pattern to bind the value, or `None` to bind to the same key
+    pub pattern: Option<Pattern<L>>,
+    /// The default value used if the key is not found
+    pub default: Option<Expression<L>>,
+}
+
+impl<L> PartialEq for KeyPattern<L> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key && self.pattern == other.pattern && self.default == other.default
+    }
+}
+
 /// Keys
 #[derive(Clone, Debug)]
 pub struct Key<L> {
@@ -150,6 +194,30 @@ pub enum ExpressionKind<L> {
     Array(Vec<Expression<L>>),
     /// An inline table
     InlineTable(Vec<(Key<L>, Expression<L>)>),
+    /// A key reference like `key`
+    Key(Key<L>),
+    /// A key reference to the root scope like `.key`
+    Root(Key<L>),
+    /// A built-in value like `@true`
+    Builtin(Key<L>),
+    /// A binary operation like `lhs + rhs`
+    BinaryOp(BinaryOpKind, Box<Expression<L>>, Box<Expression<L>>),
+    /// An unary operation like `-expr`
+    UnaryOp(UnaryOpKind, Box<Expression<L>>),
+    /// An access to a value in a table like `table.key`
+    Field(Box<Expression<L>>, Key<L>),
+    /// An access to an element like `array[index]`
+    Index(Box<Expression<L>>, Box<Expression<L>>),
+    /// An inline function like `|a, b| expr`
+    InlineFunction(Vec<Key<L>>, Box<Expression<L>>),
+    /// An import of another file like `import("file.drake")`
+    Import(String),
+    /// A function call with positional and named arguments like `f(a, key = b)`
+    Call(
+        Box<Expression<L>>,
+        Vec<Expression<L>>,
+        Vec<(Key<L>, Expression<L>)>,
+    ),
 }
 
 impl<L> PartialEq for ExpressionKind<L> {
@@ -158,11 +226,75 @@ impl<L> PartialEq for ExpressionKind<L> {
             (Self::Literal(lit1), Self::Literal(lit2)) => lit1 == lit2,
             (Self::Array(arr1), Self::Array(arr2)) => arr1 == arr2,
             (Self::InlineTable(table1), Self::InlineTable(table2)) => table1 == table2,
+            (Self::Key(key1), Self::Key(key2)) => key1 == key2,
+            (Self::Root(key1), Self::Root(key2)) => key1 == key2,
+            (Self::Builtin(key1), Self::Builtin(key2)) => key1 == key2,
+            (Self::BinaryOp(kind1, lhs1, rhs1), Self::BinaryOp(kind2, lhs2, rhs2)) => {
+                kind1 == kind2 && lhs1 == lhs2 && rhs1 == rhs2
+            }
+            (Self::UnaryOp(kind1, expr1), Self::UnaryOp(kind2, expr2)) => {
+                kind1 == kind2 && expr1 == expr2
+            }
+            (Self::Field(expr1, key1), Self::Field(expr2, key2)) => expr1 == expr2 && key1 == key2,
+            (Self::Index(expr1, index1), Self::Index(expr2, index2)) => {
+                expr1 == expr2 && index1 == index2
+            }
+            (Self::InlineFunction(params1, expr1), Self::InlineFunction(params2, expr2)) => {
+                params1 == params2 && expr1 == expr2
+            }
+            (Self::Import(path1), Self::Import(path2)) => path1 == path2,
+            (Self::Call(func1, args1, named1), Self::Call(func2, args2, named2)) => {
+                func1 == func2 && args1 == args2 && named1 == named2
+            }
             _ => false,
         }
     }
 }
 
+/// Kinds of binary operators
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BinaryOpKind {
+    /// An addition (`+`)
+    Add,
+    /// A subtraction (`-`)
+    Sub,
+    /// A multiplication (`*`)
+    Mul,
+    /// A division (`/`)
+    Div,
+    /// A remainder (`%`)
+    Rem,
+    /// An equality (`==`)
+    Eq,
+    /// An inequality (`!=`)
+    Ne,
+    /// A less-than comparison (`<`)
+    Lt,
+    /// A less-than-or-equal comparison (`<=`)
+    Le,
+    /// A greater-than comparison (`>`)
+    Gt,
+    /// A greater-than-or-equal comparison (`>=`)
+    Ge,
+    /// A logical conjunction (`&&`)
+    And,
+    /// A logical disjunction (`||`)
+    Or,
+}
+
+/// Kinds of unary operators
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UnaryOpKind {
+    /// A negation (`-`)
+    Neg,
+    /// An identity (`+`)
+    Pos,
+    /// A logical negation (`!`)
+    Not,
+}
+
 /// Values of literals
 #[derive(Clone, Debug, PartialEq)]
 #[non_exhaustive]
@@ -170,7 +302,10 @@ pub enum Literal {
     Character(char),
     String(String),
     Integer(u64),
+    NegativeInteger(i64),
     Float(f64),
+    Boolean(bool),
+    Null,
 }
 
 impl<L> fmt::Display for Statement<L> {
@@ -189,6 +324,16 @@ impl<L> fmt::Display for Statement<L> {
             StatementKind::TableHeader(TableHeaderKind::Array, ref pat, None) => {
                 write!(f, "[[{pat}]]")
             }
+            StatementKind::ExpressionFunction(ref key, ref params, ref exp) => {
+                write!(f, "fn {key}(")?;
+                parameters(f, params)?;
+                write!(f, ") = {exp}")
+            }
+            StatementKind::TableFunction(ref key, ref params) => {
+                write!(f, "[fn {key}(")?;
+                parameters(f, params)?;
+                write!(f, ")]")
+            }
         }
     }
 }
@@ -217,6 +362,35 @@ impl<L> fmt::Display for Expression<L> {
                 }
                 write!(f, "}}")
             }
+            ExpressionKind::Key(ref key) => key.fmt(f),
+            ExpressionKind::Root(ref key) => write!(f, ".{key}"),
+            ExpressionKind::Builtin(ref key) => write!(f, "@{key}"),
+            ExpressionKind::BinaryOp(kind, ref lhs, ref rhs) => write!(f, "({lhs} {kind} {rhs})"),
+            ExpressionKind::UnaryOp(kind, ref expr) => write!(f, "{kind}{expr}"),
+            ExpressionKind::Field(ref expr, ref key) => write!(f, "{expr}.{key}"),
+            ExpressionKind::Index(ref expr, ref index) => write!(f, "{expr}[{index}]"),
+            ExpressionKind::InlineFunction(ref params, ref expr) => {
+                write!(f, "|")?;
+                parameters(f, params)?;
+                write!(f, "| {expr}")
+            }
+            ExpressionKind::Import(ref path) => write!(f, "import({path:?})"),
+            ExpressionKind::Call(ref func, ref args, ref named) => {
+                write!(f, "{func}(")?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ", ")?;
+                    }
+                    arg.fmt(f)?;
+                }
+                for (i, (key, arg)) in named.iter().enumerate() {
+                    if i != 0 || !args.is_empty() {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{key} = {arg}")?;
+                }
+                write!(f, ")")
+            }
         }
     }
 }
@@ -226,10 +400,67 @@ impl<L> fmt::Display for Pattern<L> {
         match self.kind {
             PatternKind::Key(ref key) => key.fmt(f),
             PatternKind::Builtin(ref key) => write!(f, "@{key}"),
+            PatternKind::Dotted(ref keys) => dotted(f, keys),
+            PatternKind::Root(ref keys) => {
+                write!(f, ".")?;
+                dotted(f, keys)
+            }
+            PatternKind::Array(ref pats, ref rest) => {
+                write!(f, "[")?;
+                for (i, pat) in pats.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ", ")?;
+                    }
+                    pat.fmt(f)?;
+                }
+                if let Some(rest) = rest {
+                    if !pats.is_empty() {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "..{rest}")?;
+                }
+                write!(f, "]")
+            }
+            PatternKind::Table(ref keys) => {
+                write!(f, "{{")?;
+                for (i, key) in keys.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", key.key)?;
+                    if let Some(ref pat) = key.pattern {
+                        write!(f, " = {pat}")?;
+                    }
+                    if let Some(ref default) = key.default {
+                        write!(f, " ? {default}")?;
+                    }
+                }
+                write!(f, "}}")
+            }
         }
     }
 }
 
+fn parameters<L>(f: &mut fmt::Formatter<'_>, params: &[Key<L>]) -> fmt::Result {
+    for (i, param) in params.iter().enumerate() {
+        if i != 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{param}")?;
+    }
+    Ok(())
+}
+
+fn dotted<L>(f: &mut fmt::Formatter<'_>, keys: &[Key<L>]) -> fmt::Result {
+    for (i, key) in keys.iter().enumerate() {
+        if i != 0 {
+            write!(f, ".")?;
+        }
+        write!(f, "{key}")?;
+    }
+    Ok(())
+}
+
 impl<L> fmt::Display for Key<L> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.kind {
@@ -239,13 +470,46 @@ impl<L> fmt::Display for Key<L> {
     }
 }
 
+impl fmt::Display for BinaryOpKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Add => write!(f, "+"),
+            Self::Sub => write!(f, "-"),
+            Self::Mul => write!(f, "*"),
+            Self::Div => write!(f, "/"),
+            Self::Rem => write!(f, "%"),
+            Self::Eq => write!(f, "=="),
+            Self::Ne => write!(f, "!="),
+            Self::Lt => write!(f, "<"),
+            Self::Le => write!(f, "<="),
+            Self::Gt => write!(f, ">"),
+            Self::Ge => write!(f, ">="),
+            Self::And => write!(f, "&&"),
+            Self::Or => write!(f, "||"),
+        }
+    }
+}
+
+impl fmt::Display for UnaryOpKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Neg => write!(f, "-"),
+            Self::Pos => write!(f, "+"),
+            Self::Not => write!(f, "!"),
+        }
+    }
+}
+
 impl fmt::Display for Literal {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Character(c) => write!(f, "{c:?}"),
             Self::String(s) => write!(f, "{s:?}"),
             Self::Integer(i) => i.fmt(f),
+            Self::NegativeInteger(i) => i.fmt(f),
             Self::Float(fl) => fl.fmt(f),
+            Self::Boolean(b) => b.fmt(f),
+            Self::Null => write!(f, "@null"),
         }
     }
 }
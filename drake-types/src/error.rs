@@ -1,4 +1,10 @@
+#[cfg(test)]
+mod tests;
+
+use alloc::string::String;
+use alloc::vec::Vec;
 use core::convert::Infallible;
+use core::fmt;
 use core::ops::Range;
 use somen::error::{Expects, ParseError};
 
@@ -16,13 +22,186 @@ pub enum Error<L> {
     BuiltinNotFound {
         span: Range<L>,
     },
+    BuiltinReadOnly {
+        span: Range<L>,
+    },
+    BuiltinWriteOnly {
+        span: Range<L>,
+    },
     NotSupported {
         feature: &'static str,
         span: Range<L>,
     },
+    TypeMismatch {
+        expected: &'static str,
+        span: Range<L>,
+    },
+    NotRenderable {
+        filetype: &'static str,
+        span: Range<L>,
+    },
+    UnknownFileType {
+        span: Range<L>,
+    },
+    FileTypeNotSpecified,
+    KeyNotFound {
+        span: Range<L>,
+    },
+    InaccessibleKey {
+        found: Range<L>,
+        defined: Range<L>,
+    },
+    Overflow {
+        span: Range<L>,
+    },
+    DivisionByZero {
+        span: Range<L>,
+    },
+    FieldNotFound {
+        span: Range<L>,
+    },
+    IndexOutOfRange {
+        len: usize,
+        span: Range<L>,
+    },
+    PatternMismatch {
+        expected: &'static str,
+        pattern: Range<L>,
+        span: Range<L>,
+    },
+    LengthMismatch {
+        expected: usize,
+        rest: bool,
+        found: usize,
+        pattern: Range<L>,
+        span: Range<L>,
+    },
+    MissingKey {
+        found: Range<L>,
+        span: Range<L>,
+    },
+    ArityMismatch {
+        expected: usize,
+        found: usize,
+        span: Range<L>,
+        defined: Range<L>,
+    },
+    MissingArgument {
+        parameter: Range<L>,
+        span: Range<L>,
+    },
+    UnknownArgument {
+        span: Range<L>,
+        defined: Range<L>,
+    },
+    DuplicateArgument {
+        found: Range<L>,
+        existing: Range<L>,
+    },
+    RecursionLimit {
+        limit: usize,
+        span: Range<L>,
+    },
+    FunctionNotRenderable {
+        span: Range<L>,
+    },
+    ImportCycle {
+        chain: Vec<String>,
+        span: Range<L>,
+    },
+    ModuleNotFound {
+        span: Range<L>,
+    },
+    InvalidData {
+        filetype: &'static str,
+        message: String,
+        span: Range<L>,
+    },
     Unexpected,
 }
 
+impl<L> Error<L> {
+    /// Gets the range where the error occurred.
+    pub fn span(&self) -> Option<&Range<L>> {
+        match self {
+            Self::ParseError { span, .. }
+            | Self::BuiltinNotFound { span }
+            | Self::BuiltinReadOnly { span }
+            | Self::BuiltinWriteOnly { span }
+            | Self::NotSupported { span, .. }
+            | Self::TypeMismatch { span, .. }
+            | Self::NotRenderable { span, .. }
+            | Self::UnknownFileType { span }
+            | Self::KeyNotFound { span }
+            | Self::Overflow { span }
+            | Self::DivisionByZero { span }
+            | Self::FieldNotFound { span }
+            | Self::IndexOutOfRange { span, .. }
+            | Self::PatternMismatch { span, .. }
+            | Self::LengthMismatch { span, .. }
+            | Self::ArityMismatch { span, .. }
+            | Self::MissingArgument { span, .. }
+            | Self::UnknownArgument { span, .. }
+            | Self::RecursionLimit { span, .. }
+            | Self::FunctionNotRenderable { span }
+            | Self::ImportCycle { span, .. }
+            | Self::ModuleNotFound { span }
+            | Self::InvalidData { span, .. } => Some(span),
+            Self::DuplicateKey { found, .. }
+            | Self::InaccessibleKey { found, .. }
+            | Self::MissingKey { found, .. }
+            | Self::DuplicateArgument { found, .. } => Some(found),
+            Self::FileTypeNotSpecified | Self::Unexpected => None,
+        }
+    }
+}
+
+impl<L> fmt::Display for Error<L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ParseError { expects, .. } => write!(f, "expected {expects}"),
+            Self::DuplicateKey { .. } => write!(f, "the key is already defined"),
+            Self::BuiltinNotFound { .. } => write!(f, "no such built-in key"),
+            Self::BuiltinReadOnly { .. } => write!(f, "the built-in key is read-only"),
+            Self::BuiltinWriteOnly { .. } => write!(f, "the built-in key is write-only"),
+            Self::NotSupported { feature, .. } => write!(f, "not supported yet: {feature}"),
+            Self::TypeMismatch { expected, .. } => {
+                write!(f, "mismatched types, expected {expected}")
+            }
+            Self::NotRenderable { filetype, .. } => {
+                write!(f, "the value can't be rendered to {filetype}")
+            }
+            Self::UnknownFileType { .. } => write!(f, "unknown file type"),
+            Self::FileTypeNotSpecified => write!(f, "the file type is not specified"),
+            Self::KeyNotFound { .. } => write!(f, "no such key"),
+            Self::InaccessibleKey { .. } => write!(f, "the key is not accessible from here"),
+            Self::Overflow { .. } => write!(f, "the result is out of range"),
+            Self::DivisionByZero { .. } => write!(f, "attempt to divide by zero"),
+            Self::FieldNotFound { .. } => write!(f, "no such key in the table"),
+            Self::IndexOutOfRange { .. } => write!(f, "the index is out of range"),
+            Self::PatternMismatch { expected, .. } => {
+                write!(f, "mismatched types, expected {expected}")
+            }
+            Self::LengthMismatch { .. } => {
+                write!(f, "the length of the array doesn't match the pattern")
+            }
+            Self::MissingKey { .. } => write!(f, "the table doesn't have the key"),
+            Self::ArityMismatch { .. } => {
+                write!(f, "the number of arguments doesn't match the function")
+            }
+            Self::MissingArgument { .. } => write!(f, "an argument is missing"),
+            Self::UnknownArgument { .. } => write!(f, "no such parameter in the function"),
+            Self::DuplicateArgument { .. } => write!(f, "the argument is already specified"),
+            Self::RecursionLimit { .. } => write!(f, "the recursion limit is exceeded"),
+            Self::FunctionNotRenderable { .. } => write!(f, "functions cannot be rendered"),
+            Self::ImportCycle { .. } => write!(f, "the file is imported cyclically"),
+            Self::ModuleNotFound { .. } => write!(f, "the file can't be loaded"),
+            Self::InvalidData { filetype, .. } => write!(f, "the file is not valid {filetype}"),
+            Self::Unexpected => write!(f, "an unexpected error occurred"),
+        }
+    }
+}
+
 impl<L> From<ParseError<L, Infallible>> for Error<L> {
     fn from(err: ParseError<L, Infallible>) -> Self {
         match err {
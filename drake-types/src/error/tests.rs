@@ -0,0 +1,58 @@
+use alloc::string::{String, ToString};
+use alloc::vec;
+
+use super::Error;
+
+#[test]
+fn span() {
+    assert_eq!(
+        Error::ParseError {
+            expects: "a value".into(),
+            span: 1..2,
+        }
+        .span(),
+        Some(&(1..2))
+    );
+    assert_eq!(
+        Error::DuplicateKey {
+            found: 3..4,
+            existing: Some(0..1),
+        }
+        .span(),
+        Some(&(3..4))
+    );
+    assert_eq!(
+        Error::ImportCycle {
+            chain: vec![String::from("a.drake"), String::from("a.drake")],
+            span: 5..6,
+        }
+        .span(),
+        Some(&(5..6))
+    );
+    assert_eq!(Error::<usize>::FileTypeNotSpecified.span(), None);
+    assert_eq!(Error::<usize>::Unexpected.span(), None);
+}
+
+#[test]
+fn display() {
+    assert_eq!(
+        Error::ParseError {
+            expects: "a value".into(),
+            span: 1..2,
+        }
+        .to_string(),
+        "expected a value"
+    );
+    assert_eq!(
+        Error::NotRenderable {
+            filetype: "toml",
+            span: 0..1,
+        }
+        .to_string(),
+        "the value can't be rendered to toml"
+    );
+    assert_eq!(
+        Error::<usize>::FileTypeNotSpecified.to_string(),
+        "the file type is not specified"
+    );
+}
@@ -1,4 +1,5 @@
-use crate::ast::Literal;
+use crate::ast::{Expression, Key, Literal, Pattern};
+use alloc::boxed::Box;
 use alloc::string::String;
 use alloc::vec::Vec;
 use core::ops::Range;
@@ -79,6 +80,22 @@ pub struct Element<L> {
     pub defined: Range<L>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub enum FunctionBody<L> {
+    Expression(Expression<L>),
+    /// Value bindings evaluated into a new table
+    Table(Vec<(Pattern<L>, Expression<L>)>),
+    Builtin(BuiltinFunction),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuiltinFunction {
+    /// `@map(array, function)`
+    Map,
+    /// `@filter(array, function)`
+    Filter,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum ElementKind<L> {
     Table(Table<Element<L>>, Option<Expr<L>>),
@@ -97,4 +114,16 @@ pub enum ExprKind<L> {
     Literal(Literal),
     Array(Vec<Expr<L>>),
     Table(Table<Expr<L>>),
+    Function(Box<Function<L>>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Function<L> {
+    pub parameters: Vec<Key<L>>,
+    pub body: FunctionBody<L>,
+    /// Keys from the root scope to the scope where the function is defined
+    pub scope: Vec<Key<L>>,
+    /// Values of the function scope where the function is defined
+    pub captured: Table<Expr<L>>,
+    pub defined: Range<L>,
 }
@@ -18,6 +18,8 @@ pub enum Token {
     Identifier(Identifier),
     /// A literal
     Literal(Literal),
+    /// An invalid sequence of characters
+    Unknown,
 }
 
 /// Kinds of symbols
@@ -30,6 +32,8 @@ pub enum Symbol {
     Comma,
     /// A dot (`.`, `U+002E`)
     Dot,
+    /// A double dot (`..`)
+    DoubleDot,
     /// A backslash (`\`, `U+005C`)
     BackSlash,
     /// An underscore (`_`, `U+005F`)
@@ -44,6 +48,42 @@ pub enum Symbol {
     OpenBrace,
     /// A closing side of braces (`}`, `U+007D`)
     CloseBrace,
+    /// An opening side of parentheses (`(`, `U+0028`)
+    OpenParen,
+    /// A closing side of parentheses (`)`, `U+0029`)
+    CloseParen,
+    /// A plus sign (`+`, `U+002B`)
+    Plus,
+    /// A hyphen-minus (`-`, `U+002D`)
+    Minus,
+    /// An asterisk (`*`, `U+002A`)
+    Asterisk,
+    /// A slash (`/`, `U+002F`)
+    Slash,
+    /// A percent sign (`%`, `U+0025`)
+    Percent,
+    /// An exclamation mark (`!`, `U+0021`)
+    Exclamation,
+    /// A question mark (`?`, `U+003F`)
+    Question,
+    /// A vertical line (`|`, `U+007C`)
+    VerticalLine,
+    /// A less-than sign (`<`, `U+003C`)
+    LessThan,
+    /// A greater-than sign (`>`, `U+003E`)
+    GreaterThan,
+    /// A double equals sign (`==`)
+    Equal,
+    /// An exclamation mark and an equals sign (`!=`)
+    NotEqual,
+    /// A less-than sign and an equals sign (`<=`)
+    LessEqual,
+    /// A greater-than sign and an equals sign (`>=`)
+    GreaterEqual,
+    /// A double ampersand (`&&`)
+    And,
+    /// A double vertical line (`||`)
+    Or,
 }
 
 /// Identifiers
@@ -111,6 +151,7 @@ impl fmt::Display for Token {
             Token::Symbol(sym) => sym.fmt(f),
             Token::Identifier(id) => id.fmt(f),
             Token::Literal(lit) => lit.fmt(f),
+            Token::Unknown => write!(f, "unknown token"),
         }
     }
 }
@@ -121,6 +162,7 @@ impl fmt::Display for Symbol {
             Self::Assign => write!(f, "="),
             Self::Comma => write!(f, ","),
             Self::Dot => write!(f, "."),
+            Self::DoubleDot => write!(f, ".."),
             Self::BackSlash => write!(f, "\\"),
             Self::Underscore => write!(f, "_"),
             Self::At => write!(f, "@"),
@@ -128,6 +170,24 @@ impl fmt::Display for Symbol {
             Self::CloseBracket => write!(f, "]"),
             Self::OpenBrace => write!(f, "{{"),
             Self::CloseBrace => write!(f, "}}"),
+            Self::OpenParen => write!(f, "("),
+            Self::CloseParen => write!(f, ")"),
+            Self::Plus => write!(f, "+"),
+            Self::Minus => write!(f, "-"),
+            Self::Asterisk => write!(f, "*"),
+            Self::Slash => write!(f, "/"),
+            Self::Percent => write!(f, "%"),
+            Self::Exclamation => write!(f, "!"),
+            Self::Question => write!(f, "?"),
+            Self::VerticalLine => write!(f, "|"),
+            Self::LessThan => write!(f, "<"),
+            Self::GreaterThan => write!(f, ">"),
+            Self::Equal => write!(f, "=="),
+            Self::NotEqual => write!(f, "!="),
+            Self::LessEqual => write!(f, "<="),
+            Self::GreaterEqual => write!(f, ">="),
+            Self::And => write!(f, "&&"),
+            Self::Or => write!(f, "||"),
         }
     }
 }
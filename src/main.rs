@@ -0,0 +1,166 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use codespan_reporting::term::termcolor::ColorChoice;
+use drake_core::module::FileType;
+use drake_core::Runtime;
+use futures_executor::block_on;
+
+/// The Drake Configuration Language
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// The format of error messages
+    #[arg(long, global = true, value_enum, default_value_t = MessageFormat::Human)]
+    message_format: MessageFormat,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum MessageFormat {
+    /// Human-readable diagnostics
+    Human,
+    /// JSON objects, one per line
+    Json,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Renders a file to the destination specified by `@output`
+    Render {
+        /// The source file
+        file: PathBuf,
+        /// Overrides the file type (`json`, `yaml`, or `toml`)
+        #[arg(short, long, value_parser = filetype)]
+        format: Option<FileType>,
+        /// Overrides the destination (`-` for the standard output)
+        ///
+        /// The file type is inferred from its extension if `--format` is not specified.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Checks a file and reports all errors
+    Check {
+        /// The source file
+        file: PathBuf,
+    },
+    /// Prints tokens of a file
+    Tokens {
+        /// The source file
+        file: PathBuf,
+    },
+    /// Prints the AST of a file
+    Ast {
+        /// The source file
+        file: PathBuf,
+    },
+    /// Prints the IR of a file
+    Ir {
+        /// The source file
+        file: PathBuf,
+    },
+}
+
+fn filetype(name: &str) -> Result<FileType, String> {
+    FileType::from_name(name).ok_or_else(|| format!("unknown file type `{name}`"))
+}
+
+/// Fails if the extension of the destination is of another file type than `format`.
+fn check_extension(format: FileType, path: &str) -> io::Result<()> {
+    match FileType::from_path(path) {
+        Some(filetype) if filetype != format => Err(io::Error::other(format!(
+            "cannot write {format} to `{path}`, which is a {filetype} file"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Runs the command, and returns whether the file is processed without errors.
+fn run(cli: Cli) -> io::Result<bool> {
+    let file = match cli.command {
+        Command::Render { ref file, .. }
+        | Command::Check { ref file }
+        | Command::Tokens { ref file }
+        | Command::Ast { ref file }
+        | Command::Ir { ref file } => file.clone(),
+    };
+
+    let source = fs::read_to_string(&file)?;
+    let mut runtime = Runtime::new(file.display().to_string(), source);
+    let mut stdout = io::stdout().lock();
+
+    match cli.command {
+        Command::Render { format, output, .. } => {
+            // `--format` must agree with the extension of the destination, if any.
+            let dest = output.as_deref().filter(|path| *path != Path::new("-"));
+            let inferred = dest.and_then(|path| FileType::from_path(path.to_string_lossy()));
+            if let (Some(format), Some(dest)) = (format, dest) {
+                check_extension(format, &dest.to_string_lossy())?;
+            }
+            if let Some(rendered) = block_on(runtime.render(0, format.or(inferred))) {
+                if let (Some(format), None, Some(path)) = (format, &output, &rendered.path) {
+                    check_extension(format, path)?;
+                }
+                match output {
+                    Some(ref path) if path == Path::new("-") => {
+                        stdout.write_all(rendered.content.as_bytes())?
+                    }
+                    Some(ref path) => fs::write(path, &rendered.content)?,
+                    None => {
+                        let dir = file.parent().unwrap_or_else(|| Path::new(""));
+                        if !rendered.write(dir)? {
+                            stdout.write_all(rendered.content.as_bytes())?;
+                        }
+                    }
+                }
+            }
+        }
+        Command::Check { .. } => {
+            block_on(runtime.evaluate(0));
+        }
+        Command::Tokens { .. } => {
+            let module = runtime.get_module_mut(0).unwrap();
+            for token in block_on(module.tokenize()) {
+                writeln!(stdout, "{:?}: {:?}", token.span, token.kind)?;
+            }
+        }
+        Command::Ast { .. } => {
+            let module = runtime.get_module_mut(0).unwrap();
+            writeln!(stdout, "{:#?}", block_on(module.parse()))?;
+        }
+        Command::Ir { .. } => {
+            block_on(runtime.evaluate(0));
+            writeln!(
+                stdout,
+                "{:#?}",
+                runtime.get_module(0).unwrap().get_ir().unwrap()
+            )?;
+        }
+    }
+
+    match cli.message_format {
+        MessageFormat::Human => runtime
+            .print_diagnostics(ColorChoice::Auto)
+            .map_err(io::Error::other)?,
+        MessageFormat::Json => {
+            let json = runtime.diagnostics_json().map_err(io::Error::other)?;
+            io::stderr().lock().write_all(json.as_bytes())?;
+        }
+    }
+    Ok(runtime.diagnostics().is_empty())
+}
@@ -0,0 +1,105 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Creates an empty directory for the test.
+fn dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("drake-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Runs `drake render` on the source, and returns whether it succeeded and the output file.
+fn render(name: &str, source: &str) -> (bool, String) {
+    render_with(name, source, &[], "out.json")
+}
+
+/// Runs `drake render` on the source with the arguments in the directory of the source, and
+/// returns whether it succeeded and the content of the file `out`.
+fn render_with(name: &str, source: &str, args: &[&str], out: &str) -> (bool, String) {
+    let dir = dir(name);
+    fs::write(dir.join("main.drake"), source).unwrap();
+    fs::write(dir.join(out), "old").unwrap();
+
+    let status = Command::new(env!("CARGO_BIN_EXE_drake"))
+        .current_dir(&dir)
+        .arg("render")
+        .arg("main.drake")
+        .args(args)
+        .output()
+        .unwrap()
+        .status;
+    let output = fs::read_to_string(dir.join(out)).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    (status.success(), output)
+}
+
+#[test]
+fn success() {
+    assert_eq!(
+        render("ok", "@output = \"out.json\"\na = 1\n"),
+        (true, String::from("{\n  \"a\": 1\n}\n"))
+    );
+}
+
+#[test]
+fn errors() {
+    let cases = [
+        ("evaluation", "@output = \"out.json\"\na = 1\na = 2\n"),
+        ("parse", "@output = \"out.json\"\na = [1,\n"),
+        (
+            "missing",
+            "@output = \"out.json\"\na = import(\"missing.drake\")\n",
+        ),
+        (
+            "cycle",
+            "@output = \"out.json\"\na = import(\"main.drake\")\n",
+        ),
+    ];
+    for (name, source) in cases {
+        assert_eq!(render(name, source), (false, String::from("old")), "{name}");
+    }
+}
+
+#[test]
+fn inferred() {
+    let yaml = (true, String::from("a: 1\n"));
+    assert_eq!(
+        render_with("inferred", "a = 1\n", &["-o", "out.yaml"], "out.yaml"),
+        yaml
+    );
+    // `-o` overrides the file type inferred from `@output`.
+    assert_eq!(
+        render_with(
+            "overridden",
+            "@output = \"out.json\"\na = 1\n",
+            &["-o", "out.yaml"],
+            "out.yaml"
+        ),
+        yaml
+    );
+}
+
+#[test]
+fn conflicts() {
+    let cases = [
+        (
+            "format-output",
+            "a = 1\n",
+            &["--format", "yaml", "-o", "out.json"][..],
+        ),
+        (
+            "format-builtin",
+            "@output = \"out.json\"\na = 1\n",
+            &["--format", "yaml"][..],
+        ),
+    ];
+    for (name, source, args) in cases {
+        assert_eq!(
+            render_with(name, source, args, "out.json"),
+            (false, String::from("old")),
+            "{name}"
+        );
+    }
+}